@@ -0,0 +1,415 @@
+use crate::{
+    palette::{RgbColor, RgbParsingError},
+    sections::color_spec::{
+        parser::{LongForm, SpecString},
+        ColorSpec,
+    },
+};
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+use toml_edit::{ser::ValueSerializer, Decor, Document, Formatted, Item, Key, Table, Value};
+
+/// Tables of a template that don't contain highlight groups.
+pub(crate) const NON_HIGHLIGHT_TABLES: &[&str] = &[
+    "information",
+    "palette",
+    "global",
+    "terminal",
+    "lualine",
+    "tmux",
+    "textmate",
+    "base16",
+    "check",
+];
+
+/// The table whose `type`, `mod` and `typemod.<type>` subtables hold highlight groups, see
+/// [`semantic_token_sections`].
+pub(crate) const SEMANTIC_TOKENS_TABLE: &str = "semantic_tokens";
+
+const INDENT: &str = " ";
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CanonicalizeOptions {
+    /// Sort the highlight groups of every section by name.
+    pub sort: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CanonicalizeError {
+    #[error(transparent)]
+    Toml(#[from] toml_edit::TomlError),
+    #[error("In palette color {color}: {source}")]
+    Palette {
+        color: String,
+        source: RgbParsingError,
+    },
+    #[error("In highlight group {section_name}.{highlight_group}: {message}")]
+    ColorSpec {
+        section_name: String,
+        highlight_group: String,
+        message: String,
+    },
+}
+
+/// Rewrites a template in its canonical style.
+///
+/// Comments as well as the order of sections and highlight groups are kept, everything else is
+/// normalized: strings are double quoted, `=` is aligned within a table, palette colors are
+/// lowercase and color specs are written in their canonical spelling (see [`SpecString`]).
+pub fn canonicalize(
+    input: &str,
+    options: CanonicalizeOptions,
+) -> Result<String, CanonicalizeError> {
+    let mut document: Document = input.parse()?;
+
+    for (index, (name, item)) in document.as_table_mut().iter_mut().enumerate() {
+        let Some(table) = item.as_table_mut() else {
+            continue;
+        };
+
+        match name.get() {
+            "palette" => canonicalize_palette(table)?,
+            SEMANTIC_TOKENS_TABLE => canonicalize_semantic_tokens(table, options)?,
+            name if NON_HIGHLIGHT_TABLES.contains(&name) => canonicalize_strings(table),
+            name => canonicalize_section(name, table, options)?,
+        }
+
+        normalize_table_decor(table.decor_mut(), index == 0);
+        match name.get() {
+            SEMANTIC_TOKENS_TABLE => align_dotted_keys(table),
+            _ => align_keys(table),
+        }
+    }
+
+    let trailing = document.trailing().as_str().unwrap_or_default().to_string();
+    document.set_trailing(normalize_trailing(&trailing));
+    Ok(document.to_string())
+}
+
+fn canonicalize_palette(table: &mut Table) -> Result<(), CanonicalizeError> {
+    for (key, item) in table.iter_mut() {
+        let Some(value) = item.as_value_mut() else {
+            continue;
+        };
+        let color: RgbColor = value
+            .as_str()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|source| CanonicalizeError::Palette {
+                color: key.get().to_string(),
+                source,
+            })?;
+        replace_value(value, color.to_string().into());
+    }
+    Ok(())
+}
+
+fn canonicalize_strings(table: &mut Table) {
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Value(value) => {
+                let new = match value.as_str() {
+                    Some(string) => string.to_string().into(),
+                    None => value.clone(),
+                };
+                replace_value(value, new);
+            }
+            Item::Table(table) => {
+                canonicalize_strings(table);
+                align_keys(table);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn canonicalize_section(
+    section_name: &str,
+    table: &mut Table,
+    options: CanonicalizeOptions,
+) -> Result<(), CanonicalizeError> {
+    for (key, item) in table.iter_mut() {
+        let Some(value) = item.as_value_mut() else {
+            continue;
+        };
+        let error = |message: String| CanonicalizeError::ColorSpec {
+            section_name: section_name.to_string(),
+            highlight_group: key.get().to_string(),
+            message,
+        };
+
+        let color_spec = ColorSpec::deserialize(value.clone().into_deserializer())
+            .map_err(|err| error(err.to_string()))?;
+        let canonical = if value.is_inline_table() {
+            LongForm(&color_spec)
+                .serialize(ValueSerializer::new())
+                .map_err(|err| error(err.to_string()))?
+        } else {
+            SpecString(&color_spec).to_string().into()
+        };
+        replace_value(value, canonical);
+    }
+
+    if options.sort {
+        table.sort_values();
+    }
+    Ok(())
+}
+
+/// Canonicalizes the color specs in `[semantic_tokens]`, written as dotted keys like
+/// `typemod.variable.readonly` or in subtables like `[semantic_tokens.type]`.
+fn canonicalize_semantic_tokens(
+    table: &mut Table,
+    options: CanonicalizeOptions,
+) -> Result<(), CanonicalizeError> {
+    for (_, item) in table.iter_mut() {
+        if let Some(value) = item.as_value_mut().filter(|value| value.is_bool()) {
+            replace_value(value, value.clone());
+        }
+    }
+
+    for (name, section) in semantic_token_sections(table) {
+        canonicalize_section(&format!("{SEMANTIC_TOKENS_TABLE}.{name}"), section, options)?;
+        if !section.is_dotted() {
+            align_keys(section);
+        }
+    }
+    // `typemod` itself only holds tables, but is aligned when written as a header
+    if let Some(typemod) = table.get_mut("typemod").and_then(Item::as_table_mut) {
+        if !typemod.is_dotted() {
+            align_keys(typemod);
+        }
+    }
+    Ok(())
+}
+
+/// The subtables of `[semantic_tokens]` that hold color specs: `type`, `mod` and every table in
+/// `typemod`, with their names relative to `[semantic_tokens]`.
+pub(crate) fn semantic_token_sections(table: &mut Table) -> Vec<(String, &mut Table)> {
+    let mut sections = Vec::new();
+    for (key, item) in table.iter_mut() {
+        let Some(subtable) = item.as_table_mut() else {
+            continue;
+        };
+        match key.get() {
+            "type" | "mod" => sections.push((key.get().to_string(), subtable)),
+            "typemod" => {
+                for (token_type, item) in subtable.iter_mut() {
+                    if let Some(section) = item.as_table_mut() {
+                        sections.push((format!("typemod.{}", token_type.get()), section));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    sections
+}
+
+/// The tables of a template that hold highlight groups, including the ones of
+/// `[semantic_tokens]`.
+pub(crate) fn highlight_tables(document: &mut Document) -> Vec<&mut Table> {
+    let mut tables = Vec::new();
+    for (name, item) in document.as_table_mut().iter_mut() {
+        let Some(table) = item.as_table_mut() else {
+            continue;
+        };
+        match name.get() {
+            SEMANTIC_TOKENS_TABLE => tables.extend(
+                semantic_token_sections(table)
+                    .into_iter()
+                    .map(|(_, table)| table),
+            ),
+            name if NON_HIGHLIGHT_TABLES.contains(&name) => (),
+            _ => tables.push(table),
+        }
+    }
+    tables
+}
+
+/// Replaces `value` while keeping its trailing comment.
+fn replace_value(value: &mut Value, mut new: Value) {
+    if let Value::String(string) = &mut new {
+        *string = Formatted::new(string.value().clone());
+    }
+    let suffix = value
+        .decor()
+        .suffix()
+        .and_then(|suffix| suffix.as_str())
+        .map(str::trim)
+        .filter(|suffix| !suffix.is_empty())
+        .map(|comment| format!(" {comment}"))
+        .unwrap_or_default();
+    *value = new;
+    *value.decor_mut() = Decor::new(" ", suffix);
+}
+
+/// Aligns `=` over dotted keys, e.g. `type.interface` and `typemod.variable.readonly`.
+fn align_dotted_keys(table: &mut Table) {
+    fn dotted_width(table: &Table) -> usize {
+        table
+            .iter()
+            .filter_map(|(key, item)| {
+                let len = Key::new(key).display_repr().len();
+                match item {
+                    Item::Value(_) => Some(len),
+                    Item::Table(table) if table.is_dotted() => Some(len + 1 + dotted_width(table)),
+                    _ => None,
+                }
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn align_dotted(table: &mut Table, width: usize, path_len: usize, first: &mut bool) {
+        for (mut key, item) in table.iter_mut() {
+            let prefix = if path_len == 0 {
+                let prefix = key
+                    .decor()
+                    .prefix()
+                    .and_then(|prefix| prefix.as_str())
+                    .unwrap_or_default();
+                normalize_comments(prefix, *first, INDENT) + INDENT
+            } else {
+                String::new()
+            };
+            let len = path_len + key.display_repr().len();
+            match item {
+                Item::Value(_) => {
+                    let padding = " ".repeat(width - len + 1);
+                    *key.decor_mut() = Decor::new(prefix, padding);
+                }
+                Item::Table(table) if table.is_dotted() => {
+                    *key.decor_mut() = Decor::new(prefix, "");
+                    align_dotted(table, width, len + 1, first);
+                }
+                _ => continue,
+            }
+            *first = false;
+        }
+    }
+
+    align_dotted(table, dotted_width(table), 0, &mut true);
+}
+
+fn align_keys(table: &mut Table) {
+    let width = table
+        .iter_mut()
+        .filter(|(_, item)| item.is_value())
+        .map(|(key, _)| key.display_repr().len())
+        .max()
+        .unwrap_or_default();
+
+    let mut first = true;
+    for (mut key, item) in table.iter_mut() {
+        if !item.is_value() {
+            continue;
+        }
+        let prefix = key
+            .decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .unwrap_or_default();
+        let prefix = normalize_comments(prefix, first, INDENT) + INDENT;
+        let padding = " ".repeat(width - key.display_repr().len() + 1);
+        *key.decor_mut() = Decor::new(prefix, padding);
+        first = false;
+    }
+}
+
+fn normalize_table_decor(decor: &mut Decor, first: bool) {
+    let prefix = decor
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or_default();
+    let mut prefix = normalize_comments(prefix, true, "");
+    if !first {
+        prefix.insert(0, '\n');
+    }
+    let suffix = decor
+        .suffix()
+        .and_then(|suffix| suffix.as_str())
+        .map(str::trim)
+        .filter(|suffix| !suffix.is_empty())
+        .map(|comment| format!(" {comment}"))
+        .unwrap_or_default();
+    *decor = Decor::new(prefix, suffix);
+}
+
+/// Keeps the comment lines after the last table, separated from it by a blank line.
+fn normalize_trailing(raw: &str) -> String {
+    // unlike a decor there is no key after the last line
+    let comments = normalize_comments(&format!("{raw}\n"), true, INDENT);
+    let comments = comments.trim_end_matches('\n');
+    if comments.is_empty() {
+        String::new()
+    } else {
+        format!("\n{comments}\n")
+    }
+}
+
+/// Keeps the comment lines of a decor, collapsing runs of blank lines into one.
+///
+/// Leading blank lines are dropped when `trim_start` is set.
+fn normalize_comments(raw: &str, trim_start: bool, indent: &str) -> String {
+    let mut lines = raw.split('\n').collect::<Vec<_>>();
+    // the last line is the indentation in front of the key/header
+    lines.pop();
+
+    let mut output = String::new();
+    let mut blank = trim_start;
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            if !blank {
+                output.push('\n');
+            }
+            blank = true;
+        } else {
+            output.push_str(indent);
+            output.push_str(line);
+            output.push('\n');
+            blank = false;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semantic_tokens_are_canonicalized() {
+        let input = r#"[semantic_tokens]
+link_treesitter = true
+type.interface = 'fg - - - -'
+mod.readonly = "- - bi"
+typemod.variable.readonly = { fg = "fg", style = "ib", bg = "-" }
+
+[semantic_tokens.typemod.function]
+defaultLibrary = "fg -"
+"#;
+        let expected = r#"[semantic_tokens]
+ link_treesitter           = true
+ type.interface            = "fg"
+ mod.readonly              = "- - ib"
+ typemod.variable.readonly = { fg = "fg", style = "ib" }
+
+[semantic_tokens.typemod.function]
+ defaultLibrary = "fg"
+"#;
+        let output = canonicalize(input, CanonicalizeOptions::default()).unwrap();
+        assert_eq!(output, expected);
+        assert_eq!(
+            canonicalize(&output, CanonicalizeOptions::default()).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn semantic_tokens_report_the_group() {
+        let input = "[semantic_tokens]\ntype.interface = \"fg - x\"\n";
+        let err = canonicalize(input, CanonicalizeOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("semantic_tokens.type.interface"));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::sections::MissingPaletteColor;
+use serde::{Deserialize, Serialize};
+
+pub mod contrast;
+pub mod coverage;
+pub mod cvd;
+
+/// The `[check]` section, thresholds enforced by `colorgen-nvim check`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CheckSection {
+    /// The minimum WCAG 2.1 contrast ratio of text, between 1 and 21, defaults to
+    /// [`contrast::DEFAULT_WCAG`].
+    pub wcag: Option<f64>,
+    /// The minimum absolute APCA lightness contrast (Lc) of text, between 0 and about 106,
+    /// defaults to [`contrast::DEFAULT_APCA`].
+    pub apca: Option<f64>,
+    /// Highlight groups that decorate the UI rather than hold text to read, like `LineNr`,
+    /// defaults to [`contrast::DEFAULT_UI`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ui: Vec<String>,
+    /// The minimum WCAG 2.1 contrast ratio of the `ui` groups, defaults to
+    /// [`contrast::DEFAULT_UI_WCAG`].
+    pub ui_wcag: Option<f64>,
+    /// The minimum absolute APCA Lc of the `ui` groups, defaults to
+    /// [`contrast::DEFAULT_UI_APCA`].
+    pub ui_apca: Option<f64>,
+    /// Highlight groups that are allowed to have a low contrast, defaults to
+    /// [`contrast::DEFAULT_IGNORE`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    /// The minimum CIEDE2000 difference between the groups of `distinct`, with normal vision
+    /// as well as simulated protanopia, deuteranopia and tritanopia, defaults to
+    /// [`cvd::DEFAULT_DELTA_E`].
+    pub delta_e: Option<f64>,
+    /// Sets of highlight groups that must be distinguishable from each other, defaults to
+    /// [`cvd::DEFAULT_DISTINCT`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distinct: Vec<Vec<String>>,
+}
+
+impl CheckSection {
+    /// The minimum WCAG contrast and APCA Lc of a highlight group.
+    pub fn contrast_thresholds(&self, hl_group: &str) -> (f64, f64) {
+        let ui = if self.ui.is_empty() {
+            contrast::DEFAULT_UI.contains(&hl_group)
+        } else {
+            self.ui.iter().any(|group| group == hl_group)
+        };
+        if ui {
+            (
+                self.ui_wcag.unwrap_or(contrast::DEFAULT_UI_WCAG),
+                self.ui_apca.unwrap_or(contrast::DEFAULT_UI_APCA),
+            )
+        } else {
+            (
+                self.wcag.unwrap_or(contrast::DEFAULT_WCAG),
+                self.apca.unwrap_or(contrast::DEFAULT_APCA),
+            )
+        }
+    }
+
+    /// Whether a highlight group is allowed to have a low contrast.
+    pub fn is_ignored(&self, hl_group: &str) -> bool {
+        if self.ignore.is_empty() {
+            contrast::DEFAULT_IGNORE.contains(&hl_group)
+        } else {
+            self.ignore.iter().any(|group| group == hl_group)
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckError {
+    #[error(transparent)]
+    MissingPaletteColor(#[from] MissingPaletteColor),
+    #[error("{}", failed_message(*contrast, *distinct))]
+    Failed { contrast: usize, distinct: usize },
+}
+
+fn failed_message(contrast: usize, distinct: usize) -> String {
+    let mut messages = Vec::new();
+    if contrast > 0 {
+        messages.push(format!(
+            "{contrast} highlight group(s) are below the contrast thresholds"
+        ));
+    }
+    if distinct > 0 {
+        messages.push(format!(
+            "{distinct} pair(s) of highlight groups aren't distinguishable"
+        ));
+    }
+    messages.join(", ")
+}
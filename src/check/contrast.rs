@@ -0,0 +1,311 @@
+use crate::{
+    check::CheckSection,
+    palette::{srgb_to_linear, RgbColor},
+    sections::color_spec::{parser::Color, Style},
+    Template,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// The minimum WCAG contrast of text, level AA of WCAG 2.1 for normal sized text.
+pub const DEFAULT_WCAG: f64 = 4.5;
+/// The minimum APCA Lc of text, APCA's minimum for larger text like code in an editor.
+pub const DEFAULT_APCA: f64 = 45.0;
+/// The minimum WCAG contrast of UI decorations, the one of WCAG 2.1 for non-text elements.
+pub const DEFAULT_UI_WCAG: f64 = 3.0;
+/// The minimum APCA Lc of UI decorations, APCA's minimum for non-text elements.
+pub const DEFAULT_UI_APCA: f64 = 30.0;
+
+/// Highlight groups that decorate the UI, used when `[check]` doesn't set `ui`.
+pub const DEFAULT_UI: &[&str] = &[
+    "LineNr",
+    "LineNrAbove",
+    "LineNrBelow",
+    "FoldColumn",
+    "SignColumn",
+    "VertSplit",
+    "WinSeparator",
+    "FloatBorder",
+    "Conceal",
+    "SpecialKey",
+    "TabLineFill",
+    "GitSignsAdd",
+    "GitSignsChange",
+    "GitSignsDelete",
+    "IndentBlanklineChar",
+    "IblIndent",
+];
+
+/// Highlight groups that are hidden on purpose, used when `[check]` doesn't set `ignore`.
+pub const DEFAULT_IGNORE: &[&str] = &["EndOfBuffer", "NonText", "Whitespace"];
+
+/// The WCAG 2.1 contrast ratio of two colors, from 1 to 21.
+pub fn wcag_contrast(a: RgbColor, b: RgbColor) -> f64 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn relative_luminance(RgbColor([r, g, b]): RgbColor) -> f64 {
+    let [r, g, b] = [r, g, b].map(|channel| srgb_to_linear(channel as f64 / 255.0));
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// The APCA lightness contrast (Lc) of text on a background, following APCA 0.0.98G.
+///
+/// Positive for dark text on a light background, negative for light text on a dark one.
+pub fn apca_contrast(text: RgbColor, background: RgbColor) -> f64 {
+    let (text, background) = (apca_luminance(text), apca_luminance(background));
+    if (background - text).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let lc = if background > text {
+        let sapc = (background.powf(0.56) - text.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        let sapc = (background.powf(0.65) - text.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+    lc * 100.0
+}
+
+/// The screen luminance estimate of APCA, with a soft clamp for near black colors.
+fn apca_luminance(RgbColor([r, g, b]): RgbColor) -> f64 {
+    let [r, g, b] = [r, g, b].map(|channel| (channel as f64 / 255.0).powf(2.4));
+    let luminance = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    if luminance < 0.022 {
+        luminance + (0.022 - luminance).powf(1.414)
+    } else {
+        luminance
+    }
+}
+
+/// The contrast of a highlight group's effective colors.
+#[derive(Debug, Clone)]
+pub struct ContrastEntry<'a> {
+    pub hl_group: &'a str,
+    pub fg: RgbColor,
+    pub bg: RgbColor,
+    pub wcag: f64,
+    pub apca: f64,
+    /// Whether the group only paints a background: it has no text color of its own, or its
+    /// text is in the color of the background.
+    pub background_only: bool,
+    /// Whether the WCAG contrast is below the threshold of the group.
+    pub below_wcag: bool,
+    /// Whether the APCA Lc is below the threshold of the group.
+    pub below_apca: bool,
+}
+
+/// The contrast of every highlight group that sets a color, worst first.
+///
+/// The entries are marked by [`ContrastReport::check`].
+pub struct ContrastReport<'a> {
+    pub entries: Vec<ContrastEntry<'a>>,
+}
+
+impl<'a> ContrastReport<'a> {
+    /// Resolves the colors of every group, following links. Colors that aren't set are taken
+    /// from `Normal` and `reverse` swaps them.
+    pub fn new(template: &'a Template) -> Self {
+        let sections = &template.sections;
+        let palette = &template.palette;
+        let resolve = |color: &Option<Color>| color.as_ref().and_then(|c| c.resolve(palette));
+        let normal = sections.resolve("Normal");
+        let normal_fg = normal.and_then(|normal| resolve(&normal.fg));
+        let normal_bg = normal.and_then(|normal| resolve(&normal.bg));
+
+        let mut entries = Vec::new();
+        let mut seen = Vec::new();
+        for section in template.sections.0.values() {
+            for hl_group in section.0.keys() {
+                if seen.contains(&hl_group) {
+                    continue;
+                }
+                seen.push(hl_group);
+
+                let Some(format) = sections.resolve(hl_group) else {
+                    continue;
+                };
+                let reverse = format
+                    .style
+                    .is_some_and(|style| style.contains(Style::REVERSE));
+                if format.fg.is_none() && format.bg.is_none() && !reverse {
+                    continue;
+                }
+                // the text takes the background's color when reversed
+                let text = if reverse { &format.bg } else { &format.fg };
+                let own_text = resolve(text).is_some();
+                let (Some(mut fg), Some(mut bg)) = (
+                    resolve(&format.fg).or(normal_fg),
+                    resolve(&format.bg).or(normal_bg),
+                ) else {
+                    continue;
+                };
+                if reverse {
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+
+                entries.push(ContrastEntry {
+                    hl_group,
+                    fg,
+                    bg,
+                    wcag: wcag_contrast(fg, bg),
+                    apca: apca_contrast(fg, bg),
+                    background_only: !own_text || fg == bg,
+                    below_wcag: false,
+                    below_apca: false,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| a.wcag.total_cmp(&b.wcag));
+        ContrastReport { entries }
+    }
+
+    /// Marks the entries below the thresholds of their group, ignored groups and groups that
+    /// only paint a background are skipped.
+    ///
+    /// Returns the number of groups below a threshold.
+    pub fn check(&mut self, thresholds: &CheckSection) -> usize {
+        let mut failures = 0;
+        for entry in &mut self.entries {
+            if entry.background_only || thresholds.is_ignored(entry.hl_group) {
+                continue;
+            }
+            let (wcag, apca) = thresholds.contrast_thresholds(entry.hl_group);
+            entry.below_wcag = entry.wcag < wcag;
+            entry.below_apca = entry.apca.abs() < apca;
+            if entry.below_wcag || entry.below_apca {
+                failures += 1;
+            }
+        }
+        failures
+    }
+}
+
+impl Display for ContrastReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .entries
+            .iter()
+            .map(|entry| entry.hl_group.len())
+            .max()
+            .unwrap_or_default()
+            .max("group".len());
+
+        writeln!(
+            f,
+            "{:width$}  fg       bg       WCAG   APCA Lc  below",
+            "group"
+        )?;
+        for entry in &self.entries {
+            let below = match (entry.below_wcag, entry.below_apca) {
+                (true, true) => "WCAG, APCA",
+                (true, false) => "WCAG",
+                (false, true) => "APCA",
+                (false, false) => "",
+            };
+            let row = format!(
+                "{:width$}  {}  {}  {:>5.2}  {:>7.1}  {below}",
+                entry.hl_group, entry.fg, entry.bg, entry.wcag, entry.apca
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(hex: &str) -> RgbColor {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn wcag_extremes() {
+        assert!((wcag_contrast(rgb("#000000"), rgb("#ffffff")) - 21.0).abs() < 1e-9);
+        assert!((wcag_contrast(rgb("#ffffff"), rgb("#000000")) - 21.0).abs() < 1e-9);
+        assert_eq!(wcag_contrast(rgb("#777777"), rgb("#777777")), 1.0);
+    }
+
+    #[test]
+    fn wcag_reference_values() {
+        // #767676 is the lightest gray passing AA on white
+        assert!((wcag_contrast(rgb("#767676"), rgb("#ffffff")) - 4.54).abs() < 0.01);
+    }
+
+    /// The examples of the APCA 0.0.98G reference implementation.
+    #[test]
+    fn apca_reference_values() {
+        let cases = [
+            ("#888888", "#ffffff", 63.056469930209424),
+            ("#ffffff", "#888888", -68.54146436644962),
+            ("#000000", "#aaaaaa", 58.146262578561334),
+            ("#aaaaaa", "#000000", -56.24113336839742),
+        ];
+        for (text, background, lc) in cases {
+            let contrast = apca_contrast(rgb(text), rgb(background));
+            assert!(
+                (contrast - lc).abs() < 1e-6,
+                "{text} on {background}: {contrast} instead of {lc}"
+            );
+        }
+    }
+
+    #[test]
+    fn apca_same_color_is_zero() {
+        assert_eq!(apca_contrast(rgb("#888888"), rgb("#888888")), 0.0);
+    }
+
+    #[test]
+    fn background_only_groups_are_skipped() {
+        let template: Template = r##"
+[information]
+name = "sample"
+author = "me"
+background = "dark"
+
+[palette]
+bg = "#1e222a"
+fg = "#abb2bf"
+line = "#282c34"
+dim = "#2a2e36"
+
+[highlights]
+Normal = "fg bg"
+TabLineFill = "line line"
+CursorLine = "- line"
+Hidden = "bg - r"
+Comment = "dim"
+"##
+        .parse()
+        .unwrap();
+        let mut report = ContrastReport::new(&template);
+        assert_eq!(report.check(&CheckSection::default()), 1);
+
+        let entry = |hl_group: &str| {
+            report
+                .entries
+                .iter()
+                .find(|entry| entry.hl_group == hl_group)
+                .unwrap()
+        };
+        for hl_group in ["TabLineFill", "CursorLine", "Hidden"] {
+            let entry = entry(hl_group);
+            assert!(entry.background_only, "{hl_group}");
+            assert!(!entry.below_wcag && !entry.below_apca, "{hl_group}");
+        }
+        assert!(!entry("Comment").background_only);
+        assert!(entry("Comment").below_wcag);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{sections::color_spec::ColorSpec, Template};
+use std::fmt::{self, Display, Formatter};
+
+/// The Neovim release [`BUILTIN_GROUPS`] was taken from.
+pub const NEOVIM_VERSION: &str = "0.10";
+
+/// The built-in highlight groups of Neovim by category, see `:help highlight-groups`,
+/// `:help group-name`, `:help diagnostic-highlights`, `:help treesitter-highlight-groups` and
+/// `:help lsp-semantic-highlight`.
+pub const BUILTIN_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "editor",
+        &[
+            "ColorColumn",
+            "Conceal",
+            "CurSearch",
+            "Cursor",
+            "lCursor",
+            "CursorIM",
+            "CursorColumn",
+            "CursorLine",
+            "Directory",
+            "DiffAdd",
+            "DiffChange",
+            "DiffDelete",
+            "DiffText",
+            "EndOfBuffer",
+            "TermCursor",
+            "TermCursorNC",
+            "ErrorMsg",
+            "WinSeparator",
+            "Folded",
+            "FoldColumn",
+            "SignColumn",
+            "IncSearch",
+            "Substitute",
+            "LineNr",
+            "LineNrAbove",
+            "LineNrBelow",
+            "CursorLineNr",
+            "CursorLineFold",
+            "CursorLineSign",
+            "MatchParen",
+            "ModeMsg",
+            "MsgArea",
+            "MsgSeparator",
+            "MoreMsg",
+            "NonText",
+            "Normal",
+            "NormalFloat",
+            "FloatBorder",
+            "FloatTitle",
+            "FloatFooter",
+            "NormalNC",
+            "Pmenu",
+            "PmenuSel",
+            "PmenuKind",
+            "PmenuKindSel",
+            "PmenuExtra",
+            "PmenuExtraSel",
+            "PmenuSbar",
+            "PmenuThumb",
+            "Question",
+            "QuickFixLine",
+            "Search",
+            "SnippetTabstop",
+            "SpecialKey",
+            "SpellBad",
+            "SpellCap",
+            "SpellLocal",
+            "SpellRare",
+            "StatusLine",
+            "StatusLineNC",
+            "TabLine",
+            "TabLineFill",
+            "TabLineSel",
+            "Title",
+            "Visual",
+            "VisualNOS",
+            "WarningMsg",
+            "Whitespace",
+            "WildMenu",
+            "WinBar",
+            "WinBarNC",
+        ],
+    ),
+    (
+        "syntax",
+        &[
+            "Comment",
+            "Constant",
+            "String",
+            "Character",
+            "Number",
+            "Boolean",
+            "Float",
+            "Identifier",
+            "Function",
+            "Statement",
+            "Conditional",
+            "Repeat",
+            "Label",
+            "Operator",
+            "Keyword",
+            "Exception",
+            "PreProc",
+            "Include",
+            "Define",
+            "Macro",
+            "PreCondit",
+            "Type",
+            "StorageClass",
+            "Structure",
+            "Typedef",
+            "Special",
+            "SpecialChar",
+            "Tag",
+            "Delimiter",
+            "SpecialComment",
+            "Debug",
+            "Underlined",
+            "Ignore",
+            "Error",
+            "Todo",
+            "Added",
+            "Changed",
+            "Removed",
+        ],
+    ),
+    (
+        "diagnostics",
+        &[
+            "DiagnosticError",
+            "DiagnosticWarn",
+            "DiagnosticInfo",
+            "DiagnosticHint",
+            "DiagnosticOk",
+            "DiagnosticVirtualTextError",
+            "DiagnosticVirtualTextWarn",
+            "DiagnosticVirtualTextInfo",
+            "DiagnosticVirtualTextHint",
+            "DiagnosticVirtualTextOk",
+            "DiagnosticUnderlineError",
+            "DiagnosticUnderlineWarn",
+            "DiagnosticUnderlineInfo",
+            "DiagnosticUnderlineHint",
+            "DiagnosticUnderlineOk",
+            "DiagnosticFloatingError",
+            "DiagnosticFloatingWarn",
+            "DiagnosticFloatingInfo",
+            "DiagnosticFloatingHint",
+            "DiagnosticFloatingOk",
+            "DiagnosticSignError",
+            "DiagnosticSignWarn",
+            "DiagnosticSignInfo",
+            "DiagnosticSignHint",
+            "DiagnosticSignOk",
+            "DiagnosticDeprecated",
+            "DiagnosticUnnecessary",
+        ],
+    ),
+    (
+        "lsp",
+        &[
+            "LspReferenceText",
+            "LspReferenceRead",
+            "LspReferenceWrite",
+            "LspInlayHint",
+            "LspCodeLens",
+            "LspCodeLensSeparator",
+            "LspSignatureActiveParameter",
+        ],
+    ),
+    (
+        "treesitter",
+        &[
+            "@variable",
+            "@variable.builtin",
+            "@variable.parameter",
+            "@variable.parameter.builtin",
+            "@variable.member",
+            "@constant",
+            "@constant.builtin",
+            "@constant.macro",
+            "@module",
+            "@module.builtin",
+            "@label",
+            "@string",
+            "@string.documentation",
+            "@string.regexp",
+            "@string.escape",
+            "@string.special",
+            "@string.special.symbol",
+            "@string.special.path",
+            "@string.special.url",
+            "@character",
+            "@character.special",
+            "@boolean",
+            "@number",
+            "@number.float",
+            "@type",
+            "@type.builtin",
+            "@type.definition",
+            "@attribute",
+            "@attribute.builtin",
+            "@property",
+            "@function",
+            "@function.builtin",
+            "@function.call",
+            "@function.macro",
+            "@function.method",
+            "@function.method.call",
+            "@constructor",
+            "@operator",
+            "@keyword",
+            "@keyword.coroutine",
+            "@keyword.function",
+            "@keyword.operator",
+            "@keyword.import",
+            "@keyword.type",
+            "@keyword.modifier",
+            "@keyword.repeat",
+            "@keyword.return",
+            "@keyword.debug",
+            "@keyword.exception",
+            "@keyword.conditional",
+            "@keyword.conditional.ternary",
+            "@keyword.directive",
+            "@keyword.directive.define",
+            "@punctuation.delimiter",
+            "@punctuation.bracket",
+            "@punctuation.special",
+            "@comment",
+            "@comment.documentation",
+            "@comment.error",
+            "@comment.warning",
+            "@comment.todo",
+            "@comment.note",
+            "@markup.strong",
+            "@markup.italic",
+            "@markup.strikethrough",
+            "@markup.underline",
+            "@markup.heading",
+            "@markup.heading.1",
+            "@markup.heading.2",
+            "@markup.heading.3",
+            "@markup.heading.4",
+            "@markup.heading.5",
+            "@markup.heading.6",
+            "@markup.quote",
+            "@markup.math",
+            "@markup.link",
+            "@markup.link.label",
+            "@markup.link.url",
+            "@markup.raw",
+            "@markup.raw.block",
+            "@markup.list",
+            "@markup.list.checked",
+            "@markup.list.unchecked",
+            "@diff.plus",
+            "@diff.minus",
+            "@diff.delta",
+            "@tag",
+            "@tag.builtin",
+            "@tag.attribute",
+            "@tag.delimiter",
+        ],
+    ),
+    (
+        "semantic tokens",
+        &[
+            "@lsp.type.class",
+            "@lsp.type.comment",
+            "@lsp.type.decorator",
+            "@lsp.type.enum",
+            "@lsp.type.enumMember",
+            "@lsp.type.event",
+            "@lsp.type.function",
+            "@lsp.type.interface",
+            "@lsp.type.keyword",
+            "@lsp.type.macro",
+            "@lsp.type.method",
+            "@lsp.type.modifier",
+            "@lsp.type.namespace",
+            "@lsp.type.number",
+            "@lsp.type.operator",
+            "@lsp.type.parameter",
+            "@lsp.type.property",
+            "@lsp.type.regexp",
+            "@lsp.type.string",
+            "@lsp.type.struct",
+            "@lsp.type.type",
+            "@lsp.type.typeParameter",
+            "@lsp.type.variable",
+            "@lsp.mod.deprecated",
+        ],
+    ),
+];
+
+/// How the template covers a built-in highlight group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Coverage<'a> {
+    /// The group sets its own colors.
+    Defined,
+    /// The group links to another one.
+    Linked(&'a str),
+    /// A Treesitter capture that isn't defined but falls back to a parent capture that is,
+    /// e.g. `@keyword.return` to `@keyword`.
+    Inherited(String),
+    /// The group is left at Neovim's default.
+    Default,
+}
+
+impl Coverage<'_> {
+    fn of<'a>(template: &'a Template, hl_group: &str) -> Coverage<'a> {
+        match template.sections.get(hl_group) {
+            Some(ColorSpec::Color(_)) => Coverage::Defined,
+            Some(ColorSpec::Link(link)) => Coverage::Linked(link),
+            None if hl_group.starts_with('@') && !hl_group.starts_with("@lsp.") => {
+                let mut parent = hl_group;
+                while let Some((rest, _)) = parent.rsplit_once('.') {
+                    parent = rest;
+                    if template.sections.get(parent).is_some() {
+                        return Coverage::Inherited(parent.to_string());
+                    }
+                }
+                Coverage::Default
+            }
+            None => Coverage::Default,
+        }
+    }
+}
+
+/// The coverage of [`BUILTIN_GROUPS`] by category.
+pub struct CoverageReport<'a> {
+    pub categories: Vec<(&'static str, Vec<(&'static str, Coverage<'a>)>)>,
+}
+
+impl<'a> CoverageReport<'a> {
+    pub fn new(template: &'a Template) -> Self {
+        let categories = BUILTIN_GROUPS
+            .iter()
+            .map(|(category, groups)| {
+                let groups = groups
+                    .iter()
+                    .map(|hl_group| (*hl_group, Coverage::of(template, hl_group)))
+                    .collect();
+                (*category, groups)
+            })
+            .collect();
+        CoverageReport { categories }
+    }
+}
+
+impl Display for CoverageReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Built-in highlight groups of Neovim {NEOVIM_VERSION}")?;
+        for (category, groups) in &self.categories {
+            let covered = groups
+                .iter()
+                .filter(|(_, coverage)| *coverage != Coverage::Default)
+                .count();
+            writeln!(f, "\n{category} ({covered}/{})", groups.len())?;
+            for (hl_group, coverage) in groups {
+                match coverage {
+                    Coverage::Defined => writeln!(f, "  defined    {hl_group}")?,
+                    Coverage::Linked(link) => writeln!(f, "  linked     {hl_group} -> {link}")?,
+                    Coverage::Inherited(parent) => {
+                        writeln!(f, "  inherited  {hl_group} -> {parent}")?
+                    }
+                    Coverage::Default => writeln!(f, "  default    {hl_group}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
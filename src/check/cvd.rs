@@ -0,0 +1,244 @@
+use crate::{
+    palette::{linear_to_srgb, srgb_to_linear, Lab, RgbColor},
+    sections::color_spec::parser::Color,
+    Template,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// The minimum CIEDE2000 difference between distinct groups, used when `[check]` doesn't set
+/// `delta_e`. Colors that close can be told apart side by side, but not at a glance.
+pub const DEFAULT_DELTA_E: f64 = 12.0;
+
+/// Highlight groups that must be told apart, used when `[check]` doesn't set `distinct`.
+pub const DEFAULT_DISTINCT: &[&[&str]] = &[
+    &[
+        "DiagnosticError",
+        "DiagnosticWarn",
+        "DiagnosticInfo",
+        "DiagnosticHint",
+    ],
+    &["DiffAdd", "DiffChange", "DiffDelete"],
+    &["GitSignsAdd", "GitSignsChange", "GitSignsDelete"],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vision {
+    Normal,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Vision {
+    pub const ALL: [Vision; 4] = [
+        Vision::Normal,
+        Vision::Protanopia,
+        Vision::Deuteranopia,
+        Vision::Tritanopia,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Vision::Normal => "normal",
+            Vision::Protanopia => "protanopia",
+            Vision::Deuteranopia => "deuteranopia",
+            Vision::Tritanopia => "tritanopia",
+        }
+    }
+
+    /// The matrices of Machado et al. (2009) at full severity, applied to linear RGB.
+    fn matrix(self) -> Option<[[f64; 3]; 3]> {
+        match self {
+            Vision::Normal => None,
+            Vision::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            Vision::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            Vision::Tritanopia => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+        }
+    }
+
+    /// How a color is perceived with this kind of color vision.
+    pub fn simulate(self, color: RgbColor) -> RgbColor {
+        let Some(matrix) = self.matrix() else {
+            return color;
+        };
+        let linear = color
+            .0
+            .map(|channel| srgb_to_linear(channel as f64 / 255.0));
+        RgbColor(matrix.map(|row| {
+            let channel = row.iter().zip(linear).map(|(m, c)| m * c).sum::<f64>();
+            (linear_to_srgb(channel) * 255.0).round() as u8
+        }))
+    }
+}
+
+/// Two highlight groups and their color difference under every kind of color vision.
+#[derive(Debug, Clone)]
+pub struct DistinctPair {
+    pub groups: (String, String),
+    pub colors: (RgbColor, RgbColor),
+    /// The CIEDE2000 difference, in the order of [`Vision::ALL`].
+    pub delta_e: [f64; 4],
+}
+
+impl DistinctPair {
+    /// The smallest difference and the vision it occurs with.
+    pub fn worst(&self) -> (Vision, f64) {
+        Vision::ALL
+            .into_iter()
+            .zip(self.delta_e)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .expect("there is more than one vision")
+    }
+}
+
+/// The pairwise distinguishability of the groups that must be distinct, worst first.
+pub struct DistinctReport {
+    pub pairs: Vec<DistinctPair>,
+}
+
+impl DistinctReport {
+    /// Compares every pair within the `distinct` sets of `[check]` (or [`DEFAULT_DISTINCT`]),
+    /// groups that aren't defined are skipped.
+    pub fn new(template: &Template) -> Self {
+        let sets = match &template.check {
+            Some(check) if !check.distinct.is_empty() => check.distinct.clone(),
+            _ => DEFAULT_DISTINCT
+                .iter()
+                .map(|set| set.iter().map(|group| group.to_string()).collect())
+                .collect(),
+        };
+
+        let mut pairs = Vec::new();
+        for set in sets {
+            let colors = set
+                .iter()
+                .filter_map(|group| Some((group, distinguishing_color(template, group)?)))
+                .collect::<Vec<_>>();
+            for (index, (a, a_color)) in colors.iter().enumerate() {
+                for (b, b_color) in &colors[index + 1..] {
+                    let delta_e = Vision::ALL.map(|vision| {
+                        let a = Lab::from(vision.simulate(*a_color));
+                        let b = Lab::from(vision.simulate(*b_color));
+                        a.delta_e_2000(&b)
+                    });
+                    pairs.push(DistinctPair {
+                        groups: (a.to_string(), b.to_string()),
+                        colors: (*a_color, *b_color),
+                        delta_e,
+                    });
+                }
+            }
+        }
+
+        pairs.sort_by(|a, b| a.worst().1.total_cmp(&b.worst().1));
+        DistinctReport { pairs }
+    }
+
+    /// The pairs whose difference drops below `min_delta_e` (or [`DEFAULT_DELTA_E`]) with any
+    /// kind of color vision.
+    pub fn failures(&self, min_delta_e: Option<f64>) -> DistinctReport {
+        let min_delta_e = min_delta_e.unwrap_or(DEFAULT_DELTA_E);
+        let pairs = self
+            .pairs
+            .iter()
+            .filter(|pair| pair.worst().1 < min_delta_e)
+            .cloned()
+            .collect();
+        DistinctReport { pairs }
+    }
+}
+
+/// The color a group is recognized by: its background if it sets one that differs from
+/// `Normal` (like `DiffAdd`), otherwise its foreground.
+fn distinguishing_color(template: &Template, hl_group: &str) -> Option<RgbColor> {
+    let sections = &template.sections;
+    let palette = &template.palette;
+    let resolve = |color: &Option<Color>| color.as_ref().and_then(|c| c.resolve(palette));
+
+    let format = sections.resolve(hl_group)?;
+    let normal_bg = sections
+        .resolve("Normal")
+        .and_then(|normal| resolve(&normal.bg));
+    match resolve(&format.bg) {
+        Some(bg) if Some(bg.0) != normal_bg.map(|normal| normal.0) => Some(bg),
+        _ => resolve(&format.fg),
+    }
+}
+
+impl Display for DistinctReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let width = self
+            .pairs
+            .iter()
+            .map(|pair| pair.groups.0.len() + pair.groups.1.len() + 4)
+            .max()
+            .unwrap_or_default()
+            .max("groups".len());
+
+        write!(f, "{:width$}  colors          ", "groups")?;
+        for vision in Vision::ALL {
+            write!(f, "  {:>12}", vision.name())?;
+        }
+        writeln!(f)?;
+
+        for pair in &self.pairs {
+            let groups = format!("{} vs {}", pair.groups.0, pair.groups.1);
+            write!(f, "{groups:width$}  {} {}", pair.colors.0, pair.colors.1)?;
+            for delta_e in pair.delta_e {
+                write!(f, "  {delta_e:>12.1}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(hex: &str) -> RgbColor {
+        hex.parse().unwrap()
+    }
+
+    /// Primaries are mapped to the columns of Machado's matrices, encoded as sRGB.
+    #[test]
+    fn machado_primaries() {
+        let cases = [
+            (Vision::Protanopia, ["#6d5f00", "#ffe500", "#0059ff"]),
+            (Vision::Deuteranopia, ["#a39000", "#efd63a", "#003dfb"]),
+            (Vision::Tritanopia, ["#ff000f", "#00f7d9", "#006b96"]),
+        ];
+        for (vision, expected) in cases {
+            for (primary, expected) in ["#ff0000", "#00ff00", "#0000ff"].into_iter().zip(expected) {
+                assert_eq!(
+                    vision.simulate(rgb(primary)),
+                    rgb(expected),
+                    "{primary} with {}",
+                    vision.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn grays_are_kept() {
+        for vision in Vision::ALL {
+            for gray in ["#000000", "#808080", "#ffffff"] {
+                assert_eq!(vision.simulate(rgb(gray)), rgb(gray), "{}", vision.name());
+            }
+        }
+    }
+}
@@ -1,20 +1,209 @@
-use clap::Parser;
+use crate::{extras::Target, information::Background};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct ColorgenArgs {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub generate: GenerateArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Rewrite a template in the canonical style
+    Fmt(FmtArgs),
+    /// Print the palette and every highlight group with truecolor escape sequences, or write an
+    /// HTML page with code samples or a screenshot
+    Preview(PreviewArgs),
+    /// Rename legacy Treesitter groups to their `@` captures
+    Migrate(MigrateArgs),
+    /// Derive a light variant of a dark template
+    DeriveLight(DeriveLightArgs),
+    /// Check a template for missing palette colors and low contrast
+    Check(CheckArgs),
+    /// Convert a colorscheme of another format into a template
+    #[command(subcommand)]
+    Import(ImportCommand),
+    /// Create a palette
+    #[command(subcommand)]
+    Palette(PaletteCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
     /// The filename for your colorscheme
-    pub filename: PathBuf,
+    #[arg(required = true)]
+    pub filename: Option<PathBuf>,
 
     /// Write into a single file instead of writing a whole module
     #[arg(short, long, default_value_t = false)]
     pub single_file: bool,
 
+    /// Write a Vimscript colorscheme (`colors/<theme-name>.vim`) for Vim instead of Lua
+    #[arg(long, default_value_t = false, conflicts_with = "single_file")]
+    pub vim: bool,
+
     /// File/Directory to write the theme to.
     ///
     /// If used together with `--single-file` it will either write directly to the file, or if
     /// `--output` is a directory it will write into this directory under `<theme-name>.lua`.
     #[arg(short, long)]
     pub output: Option<PathBuf>,
+
+    /// Also generate themes for other programs, written to `extras/<target>/` in the output
+    /// directory
+    #[arg(short, long, value_delimiter = ',')]
+    pub targets: Vec<Target>,
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    /// The template to format in place
+    pub filename: PathBuf,
+
+    /// Sort the highlight groups of every section by name
+    #[arg(long, default_value_t = false)]
+    pub sort: bool,
+
+    /// Don't write anything, fail if the template isn't formatted
+    #[arg(long, default_value_t = false)]
+    pub check: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// Import a base16 or base24 scheme (YAML)
+    Base16(ImportArgs),
+    /// Import a Lua colorscheme made of `nvim_set_hl` calls
+    Lua(ImportColorschemeArgs),
+    /// Import a Vim colorscheme made of `hi` commands
+    Vim(ImportColorschemeArgs),
+    /// Import the output of `:highlight` or a JSON dump of `nvim_get_hl(0, {})`
+    Highlights(ImportColorschemeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// The file to import
+    pub filename: PathBuf,
+
+    /// Where to write the template, defaults to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportColorschemeArgs {
+    #[command(flatten)]
+    pub import: ImportArgs,
+
+    /// Merge colors closer than this (in Oklab, where 0.02 is barely noticeable) into one palette
+    /// color, 0 only merges identical colors
+    #[arg(long, default_value_t = 0.02)]
+    pub merge_distance: f64,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PaletteCommand {
+    /// Extract a palette from a PNG or JPEG image, e.g. a wallpaper
+    FromImage(FromImageArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct FromImageArgs {
+    /// The image to extract the colors from
+    pub filename: PathBuf,
+
+    /// The number of palette colors: `bg`, `alt_bg`, `fg`, `gray` and the accents
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u16).range(4..=64))]
+    pub count: u16,
+
+    /// Derive a palette for this background instead of the one of the image
+    #[arg(long)]
+    pub background: Option<Background>,
+
+    /// Where to write the palette, defaults to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct PreviewArgs {
+    /// The template to preview
+    pub filename: PathBuf,
+
+    /// Write a self-contained HTML page with the palette, code samples and mock UI instead
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+
+    /// Write a screenshot of an editor window as SVG instead
+    #[arg(long)]
+    pub svg: Option<PathBuf>,
+
+    /// Write a screenshot of an editor window as PNG instead
+    #[arg(long)]
+    pub png: Option<PathBuf>,
+
+    /// The scale of the PNG screenshot
+    #[arg(long, default_value_t = 2.0)]
+    pub scale: f32,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// The template to migrate in place
+    pub filename: PathBuf,
+
+    /// Only print what would be renamed
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct DeriveLightArgs {
+    /// The dark template
+    pub filename: PathBuf,
+
+    /// The name of the light variant, defaults to the name of the template with `-light`
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Where to write the template, defaults to stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    /// The template to check
+    pub filename: PathBuf,
+
+    /// Print which of Neovim's built-in highlight groups the template defines, links or leaves
+    /// at their defaults, instead of checking contrast and color vision unless `--cvd` or a
+    /// minimum is given as well
+    #[arg(long, default_value_t = false)]
+    pub coverage: bool,
+
+    /// The minimum WCAG 2.1 contrast ratio of text, overrides `wcag` in `[check]`
+    #[arg(long)]
+    pub min_wcag: Option<f64>,
+
+    /// The minimum absolute APCA Lc of text, overrides `apca` in `[check]`
+    #[arg(long)]
+    pub min_apca: Option<f64>,
+
+    /// Print how well the `distinct` highlight groups can be told apart with simulated color
+    /// vision deficiencies
+    #[arg(long, default_value_t = false)]
+    pub cvd: bool,
+
+    /// The minimum CIEDE2000 difference between `distinct` groups, overrides `delta_e` in
+    /// `[check]`
+    #[arg(long)]
+    pub min_delta_e: Option<f64>,
 }
@@ -0,0 +1,174 @@
+use crate::{
+    palette::RgbColor,
+    sections::color_spec::{parser::Color, ColorFormat},
+    terminal::{AnsiColor, Terminal},
+    Template,
+};
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+};
+
+pub mod alacritty;
+pub mod base16;
+pub mod foot;
+pub mod ghostty;
+pub mod helix;
+pub mod kitty;
+pub mod textmate;
+pub mod tmux;
+pub mod vscode;
+pub mod wezterm;
+pub mod windows_terminal;
+
+/// Themes for other programs that are generated next to the Neovim colorscheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Target {
+    Alacritty,
+    Kitty,
+    Wezterm,
+    Foot,
+    Ghostty,
+    WindowsTerminal,
+    Tmux,
+    Vscode,
+    Helix,
+    TmTheme,
+    Base16,
+    Base24,
+}
+
+impl Target {
+    /// The path of the generated file, relative to the output directory.
+    pub fn path(self, name: &str) -> PathBuf {
+        let (directory, file_name) = match self {
+            Target::Alacritty => ("alacritty", format!("{name}.toml")),
+            Target::Kitty => ("kitty", format!("{name}.conf")),
+            Target::Wezterm => ("wezterm", format!("{name}.toml")),
+            Target::Foot => ("foot", format!("{name}.ini")),
+            Target::Ghostty => ("ghostty", name.to_string()),
+            Target::WindowsTerminal => ("windows_terminal", format!("{name}.json")),
+            Target::Tmux => ("tmux", format!("{name}.tmux")),
+            Target::Vscode => ("vscode", format!("{name}-color-theme.json")),
+            Target::Helix => ("helix", format!("{name}.toml")),
+            Target::TmTheme => ("tmtheme", format!("{name}.tmTheme")),
+            Target::Base16 => ("base16", format!("{name}.yaml")),
+            Target::Base24 => ("base24", format!("{name}.yaml")),
+        };
+
+        [
+            Path::new("extras"),
+            Path::new(directory),
+            Path::new(&file_name),
+        ]
+        .iter()
+        .collect()
+    }
+
+    pub fn render(self, template: &Template) -> Result<String, ExtrasError> {
+        Ok(match self {
+            Target::Alacritty => alacritty::Alacritty(&TerminalTheme::new(template)?).to_string(),
+            Target::Kitty => kitty::Kitty(&TerminalTheme::new(template)?).to_string(),
+            Target::Wezterm => wezterm::Wezterm(&TerminalTheme::new(template)?).to_string(),
+            Target::Foot => foot::Foot(&TerminalTheme::new(template)?).to_string(),
+            Target::Ghostty => ghostty::Ghostty(&TerminalTheme::new(template)?).to_string(),
+            Target::WindowsTerminal => {
+                windows_terminal::WindowsTerminal(&TerminalTheme::new(template)?).to_string()
+            }
+            Target::Tmux => tmux::Tmux { template }.to_string(),
+            Target::Vscode => vscode::Vscode { template }.to_string(),
+            Target::Helix => helix::Helix { template }.to_string(),
+            Target::TmTheme => textmate::TmTheme { template }.to_string(),
+            Target::Base16 => base16::Base16::new(template, false)?.to_string(),
+            Target::Base24 => base16::Base16::new(template, true)?.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtrasError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(
+        "The terminal color {} isn't set in [terminal] and has no fallback in the palette",
+        .0.name()
+    )]
+    MissingTerminalColor(AnsiColor),
+    #[error("Could not find a {0} color, set it in the `Normal` highlight group or the palette")]
+    MissingColor(&'static str),
+    #[error("Unknown slot {0} in [base16], expected base00 to base17")]
+    UnknownBase16Slot(String),
+    #[error("The color {0} of [base16] isn't in the palette")]
+    MissingBase16Color(String),
+}
+
+/// The colors shared by all terminal emulator themes.
+#[derive(Debug, Clone)]
+pub struct TerminalTheme<'a> {
+    pub name: &'a str,
+    pub author: &'a str,
+    pub foreground: RgbColor,
+    pub background: RgbColor,
+    /// The cursor color and the color of the text under it.
+    pub cursor: Option<(RgbColor, RgbColor)>,
+    /// The selection background and, if set, foreground.
+    pub selection: Option<(RgbColor, Option<RgbColor>)>,
+    pub ansi: [RgbColor; 16],
+}
+
+impl<'a> TerminalTheme<'a> {
+    /// Resolves the theme from the `Normal`, `Cursor` and `Visual` highlight groups and the
+    /// `[terminal]` section.
+    pub fn new(template: &'a Template) -> Result<Self, ExtrasError> {
+        let palette = &template.palette;
+        let resolve = |color: &Option<Color>| color.as_ref().and_then(|c| c.resolve(palette));
+        let palette_color = |name: &str| palette.0.get(name).copied();
+        let normal = template.sections.resolve("Normal");
+
+        let foreground = normal
+            .and_then(|normal| resolve(&normal.fg))
+            .or_else(|| palette_color("fg"))
+            .ok_or(ExtrasError::MissingColor("foreground"))?;
+        let background = normal
+            .and_then(|normal| resolve(&normal.bg))
+            .or_else(|| palette_color("bg"))
+            .ok_or(ExtrasError::MissingColor("background"))?;
+
+        let cursor = template.sections.resolve("Cursor").and_then(|cursor| {
+            let ColorFormat { fg, bg, .. } = cursor;
+            Some((resolve(bg)?, resolve(fg).unwrap_or(background)))
+        });
+        let selection = template
+            .sections
+            .resolve("Visual")
+            .and_then(|visual| Some((resolve(&visual.bg)?, resolve(&visual.fg))));
+
+        let default_terminal = Terminal::default();
+        let terminal = template.terminal.as_ref().unwrap_or(&default_terminal);
+        let mut ansi = [RgbColor([0; 3]); 16];
+        for (color, slot) in terminal.resolve(palette).iter().zip(AnsiColor::ALL) {
+            ansi[slot.index()] = resolve(color).ok_or(ExtrasError::MissingTerminalColor(slot))?;
+        }
+
+        Ok(TerminalTheme {
+            name: &template.information.name,
+            author: &template.information.author,
+            foreground,
+            background,
+            cursor,
+            selection,
+            ansi,
+        })
+    }
+}
+
+/// Formats a color as `rrggbb`, without the leading `#`.
+pub(crate) struct BareHex(pub RgbColor);
+
+impl Display for BareHex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let RgbColor([r, g, b]) = self.0;
+        write!(f, "{r:02x}{g:02x}{b:02x}")
+    }
+}
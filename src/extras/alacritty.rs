@@ -0,0 +1,44 @@
+use crate::extras::TerminalTheme;
+use std::fmt::{self, Display, Formatter};
+
+const NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// An Alacritty color scheme in TOML, to be imported from `alacritty.toml`.
+pub struct Alacritty<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for Alacritty<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        writeln!(f, "# {} by {}", theme.name, theme.author)?;
+
+        writeln!(f, "\n[colors.primary]")?;
+        writeln!(f, "background = '{}'", theme.background)?;
+        writeln!(f, "foreground = '{}'", theme.foreground)?;
+
+        if let Some((cursor, text)) = theme.cursor {
+            writeln!(f, "\n[colors.cursor]")?;
+            writeln!(f, "cursor = '{cursor}'")?;
+            writeln!(f, "text = '{text}'")?;
+        }
+
+        if let Some((background, foreground)) = theme.selection {
+            writeln!(f, "\n[colors.selection]")?;
+            writeln!(f, "background = '{background}'")?;
+            match foreground {
+                Some(foreground) => writeln!(f, "text = '{foreground}'")?,
+                None => writeln!(f, "text = 'CellForeground'")?,
+            }
+        }
+
+        for (table, colors) in [("normal", &theme.ansi[..8]), ("bright", &theme.ansi[8..])] {
+            writeln!(f, "\n[colors.{table}]")?;
+            for (name, color) in NAMES.iter().zip(colors) {
+                writeln!(f, "{name} = '{color}'")?;
+            }
+        }
+
+        Ok(())
+    }
+}
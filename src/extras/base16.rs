@@ -0,0 +1,268 @@
+use crate::{
+    extras::ExtrasError,
+    import::base16::{BASE16_SLOTS, BASE24_SLOTS},
+    palette::{Oklab, RgbColor},
+    sections::color_spec::parser::{Color, SpecColor},
+    terminal::{AnsiColor, Terminal},
+    Template,
+};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The `[base16]` section, assigning palette colors to base16/base24 slots.
+///
+/// Slots that aren't listed are picked automatically, see [`Role`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Base16Section(pub LinkedHashMap<String, Color>);
+
+/// How a slot is filled when it isn't set in `[base16]`.
+enum Role {
+    /// The first highlight group attribute that is set, otherwise the given slot.
+    Groups(&'static [(&'static str, Attribute)], usize),
+    /// The terminal color, otherwise the palette color closest to the hue (in Oklab degrees).
+    Accent(AnsiColor, f64),
+    /// The palette color closest to the hue.
+    Hue(f64),
+    /// The midpoint of two slots.
+    Mix(usize, usize),
+    /// The achromatic palette color with the most contrast to `base00`, otherwise `base05`.
+    Extreme,
+    /// The terminal color, otherwise the given slot.
+    Bright(AnsiColor, usize),
+}
+
+#[derive(Clone, Copy)]
+enum Attribute {
+    Fg,
+    Bg,
+}
+
+/// The roles of the 24 slots, in the order of [`BASE16_SLOTS`] and [`BASE24_SLOTS`].
+///
+/// Slots only reference slots that come before them, except for `base06` which is resolved
+/// after `base07`.
+const ROLES: [Role; 24] = [
+    Role::Groups(&[("Normal", Attribute::Bg)], 5),
+    Role::Groups(
+        &[
+            ("CursorLine", Attribute::Bg),
+            ("ColorColumn", Attribute::Bg),
+            ("StatusLine", Attribute::Bg),
+            ("Pmenu", Attribute::Bg),
+        ],
+        0,
+    ),
+    Role::Groups(&[("Visual", Attribute::Bg), ("PmenuSel", Attribute::Bg)], 1),
+    Role::Groups(
+        &[
+            ("Comment", Attribute::Fg),
+            ("LineNr", Attribute::Fg),
+            ("NonText", Attribute::Fg),
+        ],
+        5,
+    ),
+    Role::Groups(
+        &[
+            ("StatusLine", Attribute::Fg),
+            ("CursorLineNr", Attribute::Fg),
+            ("Pmenu", Attribute::Fg),
+        ],
+        5,
+    ),
+    Role::Groups(&[("Normal", Attribute::Fg)], 5),
+    Role::Mix(5, 7),
+    Role::Extreme,
+    Role::Accent(AnsiColor::Red, 25.0),
+    Role::Hue(60.0),
+    Role::Accent(AnsiColor::Yellow, 100.0),
+    Role::Accent(AnsiColor::Green, 140.0),
+    Role::Accent(AnsiColor::Cyan, 195.0),
+    Role::Accent(AnsiColor::Blue, 255.0),
+    Role::Accent(AnsiColor::Magenta, 320.0),
+    Role::Hue(45.0),
+    Role::Groups(
+        &[("NormalFloat", Attribute::Bg), ("NormalSB", Attribute::Bg)],
+        0,
+    ),
+    Role::Groups(&[], 16),
+    Role::Bright(AnsiColor::BrightRed, 8),
+    Role::Bright(AnsiColor::BrightYellow, 10),
+    Role::Bright(AnsiColor::BrightGreen, 11),
+    Role::Bright(AnsiColor::BrightCyan, 12),
+    Role::Bright(AnsiColor::BrightBlue, 13),
+    Role::Bright(AnsiColor::BrightMagenta, 14),
+];
+
+/// A base16 or base24 scheme in the YAML format of tinted-theming.
+pub struct Base16<'a> {
+    pub template: &'a Template,
+    pub colors: Vec<RgbColor>,
+}
+
+impl<'a> Base16<'a> {
+    pub fn new(template: &'a Template, base24: bool) -> Result<Self, ExtrasError> {
+        let palette = &template.palette;
+        let slots = BASE16_SLOTS.iter().chain(&BASE24_SLOTS).collect::<Vec<_>>();
+
+        let mut assigned = [None; 24];
+        if let Some(section) = &template.base16 {
+            for (slot, color) in &section.0 {
+                let index = slots
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case(slot))
+                    .ok_or_else(|| ExtrasError::UnknownBase16Slot(slot.clone()))?;
+                assigned[index] = Some(color.resolve(palette).ok_or_else(|| {
+                    ExtrasError::MissingBase16Color(SpecColor(color).to_string())
+                })?);
+            }
+        }
+
+        let default_terminal = Terminal::default();
+        let terminal = template
+            .terminal
+            .as_ref()
+            .unwrap_or(&default_terminal)
+            .resolve(palette);
+        let terminal_color = |ansi: AnsiColor| {
+            terminal[ansi.index()]
+                .as_ref()
+                .and_then(|color| color.resolve(palette))
+        };
+        let group_color = |hl_group: &str, attribute: Attribute| {
+            let format = template.sections.resolve(hl_group)?;
+            let color = match attribute {
+                Attribute::Fg => &format.fg,
+                Attribute::Bg => &format.bg,
+            };
+            color.as_ref()?.resolve(palette)
+        };
+
+        let fallback_fg = group_color("Normal", Attribute::Fg)
+            .or_else(|| palette.0.get("fg").copied())
+            .ok_or(ExtrasError::MissingColor("foreground"))?;
+        let fallback_bg = group_color("Normal", Attribute::Bg)
+            .or_else(|| palette.0.get("bg").copied())
+            .ok_or(ExtrasError::MissingColor("background"))?;
+
+        let mut colors = [RgbColor([0; 3]); 24];
+        // base06 is the midpoint of base05 and base07
+        let order = (0..24).filter(|index| *index != 6).chain([6]);
+        for index in order {
+            if let Some(color) = assigned[index] {
+                colors[index] = color;
+                continue;
+            }
+            colors[index] = match &ROLES[index] {
+                Role::Groups(groups, fallback) => groups
+                    .iter()
+                    .find_map(|(hl_group, attribute)| group_color(hl_group, *attribute))
+                    .unwrap_or(match index {
+                        0 => fallback_bg,
+                        5 => fallback_fg,
+                        _ => colors[*fallback],
+                    }),
+                Role::Accent(ansi, hue) => terminal_color(*ansi)
+                    .or_else(|| closest_hue(template, *hue))
+                    .unwrap_or(colors[5]),
+                Role::Hue(hue) => closest_hue(template, *hue).unwrap_or(colors[5]),
+                Role::Mix(a, b) => mix(colors[*a], colors[*b]),
+                Role::Extreme => extreme(template, colors[0]).unwrap_or(colors[5]),
+                Role::Bright(ansi, fallback) => terminal_color(*ansi).unwrap_or(colors[*fallback]),
+            };
+        }
+
+        let len = if base24 { 24 } else { 16 };
+        Ok(Base16 {
+            template,
+            colors: colors[..len].to_vec(),
+        })
+    }
+}
+
+/// The palette color with the most chroma within 30° of the hue, otherwise the one with the
+/// closest hue. Achromatic colors are never picked.
+fn closest_hue(template: &Template, hue: f64) -> Option<RgbColor> {
+    let candidates = template
+        .palette
+        .0
+        .values()
+        .filter_map(|color| {
+            let Oklab { a, b, .. } = Oklab::from(*color);
+            let chroma = a.hypot(b);
+            let distance = (b.atan2(a).to_degrees() - hue).rem_euclid(360.0);
+            let distance = distance.min(360.0 - distance);
+            (chroma >= 0.05).then_some((*color, chroma, distance))
+        })
+        .collect::<Vec<_>>();
+
+    candidates
+        .iter()
+        .filter(|(_, _, distance)| *distance <= 30.0)
+        .max_by(|(_, a, _), (_, b, _)| a.total_cmp(b))
+        .or_else(|| {
+            candidates
+                .iter()
+                .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+        })
+        .map(|(color, _, _)| *color)
+}
+
+fn extreme(template: &Template, background: RgbColor) -> Option<RgbColor> {
+    let background = Oklab::from(background).l;
+    template
+        .palette
+        .0
+        .values()
+        .filter(|color| {
+            let Oklab { a, b, .. } = Oklab::from(**color);
+            a.hypot(b) < 0.03
+        })
+        .max_by(|a, b| {
+            let a = (Oklab::from(**a).l - background).abs();
+            let b = (Oklab::from(**b).l - background).abs();
+            a.total_cmp(&b)
+        })
+        .copied()
+}
+
+fn mix(RgbColor(a): RgbColor, RgbColor(b): RgbColor) -> RgbColor {
+    RgbColor([0, 1, 2].map(|i| ((a[i] as u16 + b[i] as u16) / 2) as u8))
+}
+
+impl Display for Base16<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let information = &self.template.information;
+        let system = if self.colors.len() == 24 {
+            "base24"
+        } else {
+            "base16"
+        };
+        writeln!(f, "system: \"{system}\"")?;
+        writeln!(f, "name: {}", YamlString(&information.name))?;
+        writeln!(f, "author: {}", YamlString(&information.author))?;
+        writeln!(f, "variant: \"{}\"", information.background)?;
+        writeln!(f, "palette:")?;
+        for (slot, color) in BASE16_SLOTS.iter().chain(&BASE24_SLOTS).zip(&self.colors) {
+            writeln!(f, "  {slot}: \"{color}\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// A double quoted YAML string.
+struct YamlString<'a>(&'a str);
+
+impl Display for YamlString<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
@@ -0,0 +1,37 @@
+use crate::extras::{BareHex, TerminalTheme};
+use std::fmt::{self, Display, Formatter};
+
+/// A foot theme, to be included from `foot.ini`.
+pub struct Foot<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for Foot<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        writeln!(f, "# {} by {}", theme.name, theme.author)?;
+
+        if let Some((cursor, text)) = theme.cursor {
+            writeln!(f, "\n[cursor]")?;
+            writeln!(f, "color={} {}", BareHex(text), BareHex(cursor))?;
+        }
+
+        writeln!(f, "\n[colors]")?;
+        writeln!(f, "foreground={}", BareHex(theme.foreground))?;
+        writeln!(f, "background={}", BareHex(theme.background))?;
+
+        for (index, color) in theme.ansi[..8].iter().enumerate() {
+            writeln!(f, "regular{index}={}", BareHex(*color))?;
+        }
+        for (index, color) in theme.ansi[8..].iter().enumerate() {
+            writeln!(f, "bright{index}={}", BareHex(*color))?;
+        }
+
+        if let Some((background, foreground)) = theme.selection {
+            writeln!(f, "selection-background={}", BareHex(background))?;
+            if let Some(foreground) = foreground {
+                writeln!(f, "selection-foreground={}", BareHex(foreground))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::extras::TerminalTheme;
+use std::fmt::{self, Display, Formatter};
+
+/// A Ghostty theme, to be placed in `~/.config/ghostty/themes`.
+pub struct Ghostty<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for Ghostty<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        writeln!(f, "# {} by {}", theme.name, theme.author)?;
+
+        writeln!(f)?;
+        for (index, color) in theme.ansi.iter().enumerate() {
+            writeln!(f, "palette = {index}={color}")?;
+        }
+
+        writeln!(f, "background = {}", theme.background)?;
+        writeln!(f, "foreground = {}", theme.foreground)?;
+
+        if let Some((cursor, text)) = theme.cursor {
+            writeln!(f, "cursor-color = {cursor}")?;
+            writeln!(f, "cursor-text = {text}")?;
+        }
+
+        if let Some((background, foreground)) = theme.selection {
+            writeln!(f, "selection-background = {background}")?;
+            if let Some(foreground) = foreground {
+                writeln!(f, "selection-foreground = {foreground}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
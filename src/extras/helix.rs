@@ -0,0 +1,359 @@
+use crate::{
+    import::toml_string,
+    sections::color_spec::{
+        parser::{Color, SpecColor},
+        ColorFormat, Style,
+    },
+    Template,
+};
+use linked_hash_map::LinkedHashMap;
+use std::fmt::{self, Display, Formatter};
+use toml_edit::Key;
+
+/// Treesitter captures whose Helix scope differs, matched by prefix.
+const CAPTURE_RENAMES: &[(&str, &str)] = &[
+    ("keyword.conditional", "keyword.control.conditional"),
+    ("keyword.repeat", "keyword.control.repeat"),
+    ("keyword.import", "keyword.control.import"),
+    ("keyword.return", "keyword.control.return"),
+    ("keyword.exception", "keyword.control.exception"),
+    ("conditional", "keyword.control.conditional"),
+    ("repeat", "keyword.control.repeat"),
+    ("include", "keyword.control.import"),
+    ("exception", "keyword.control.exception"),
+    ("method", "function.method"),
+    ("number.float", "constant.numeric.float"),
+    ("number", "constant.numeric.integer"),
+    ("float", "constant.numeric.float"),
+    ("boolean", "constant.builtin.boolean"),
+    ("character.special", "constant.character"),
+    ("character", "constant.character"),
+    ("string.escape", "constant.character.escape"),
+    ("string.regex", "string.regexp"),
+    ("module", "namespace"),
+    ("property", "variable.other.member"),
+    ("field", "variable.other.member"),
+    ("parameter", "variable.parameter"),
+    ("markup.strong", "markup.bold"),
+    ("markup.link.label", "markup.link.text"),
+    ("text.title", "markup.heading"),
+    ("text.strong", "markup.bold"),
+    ("text.emphasis", "markup.italic"),
+    ("text.uri", "markup.link.url"),
+    ("text.literal", "markup.raw"),
+    ("text.strike", "markup.strikethrough"),
+];
+
+/// Legacy `TS*` highlight groups and their Helix scope.
+const LEGACY_GROUPS: &[(&str, &str)] = &[
+    ("TSComment", "comment"),
+    ("TSString", "string"),
+    ("TSStringRegex", "string.regexp"),
+    ("TSStringEscape", "constant.character.escape"),
+    ("TSCharacter", "constant.character"),
+    ("TSNumber", "constant.numeric.integer"),
+    ("TSFloat", "constant.numeric.float"),
+    ("TSBoolean", "constant.builtin.boolean"),
+    ("TSConstant", "constant"),
+    ("TSConstBuiltin", "constant.builtin"),
+    ("TSConstMacro", "constant"),
+    ("TSKeyword", "keyword"),
+    ("TSKeywordFunction", "keyword.function"),
+    ("TSKeywordOperator", "keyword.operator"),
+    ("TSKeywordReturn", "keyword.control.return"),
+    ("TSConditional", "keyword.control.conditional"),
+    ("TSRepeat", "keyword.control.repeat"),
+    ("TSInclude", "keyword.control.import"),
+    ("TSException", "keyword.control.exception"),
+    ("TSOperator", "operator"),
+    ("TSFunction", "function"),
+    ("TSFuncBuiltin", "function.builtin"),
+    ("TSFuncMacro", "function.macro"),
+    ("TSMethod", "function.method"),
+    ("TSConstructor", "constructor"),
+    ("TSType", "type"),
+    ("TSTypeBuiltin", "type.builtin"),
+    ("TSVariable", "variable"),
+    ("TSVariableBuiltin", "variable.builtin"),
+    ("TSParameter", "variable.parameter"),
+    ("TSProperty", "variable.other.member"),
+    ("TSField", "variable.other.member"),
+    ("TSNamespace", "namespace"),
+    ("TSLabel", "label"),
+    ("TSPunctDelimiter", "punctuation.delimiter"),
+    ("TSPunctBracket", "punctuation.bracket"),
+    ("TSPunctSpecial", "punctuation.special"),
+    ("TSTag", "tag"),
+    ("TSTagAttribute", "attribute"),
+    ("TSTitle", "markup.heading"),
+    ("TSStrong", "markup.bold"),
+    ("TSEmphasis", "markup.italic"),
+    ("TSURI", "markup.link.url"),
+    ("TSLiteral", "markup.raw"),
+    ("TSStrike", "markup.strikethrough"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Attributes {
+    All,
+    Fg,
+    Bg,
+}
+
+/// UI scopes and the highlight groups they are taken from, the first group that is defined wins.
+const UI_SCOPES: &[(&[&str], Attributes, &str)] = &[
+    (&["Normal"], Attributes::Bg, "ui.background"),
+    (&["Normal"], Attributes::Fg, "ui.text"),
+    (&["NormalFloat", "Pmenu"], Attributes::All, "ui.popup"),
+    (&["NormalFloat", "Pmenu"], Attributes::All, "ui.help"),
+    (&["Pmenu"], Attributes::All, "ui.menu"),
+    (&["PmenuSel"], Attributes::All, "ui.menu.selected"),
+    (&["PmenuThumb"], Attributes::All, "ui.menu.scroll"),
+    (&["Cursor"], Attributes::All, "ui.cursor"),
+    (&["MatchParen"], Attributes::All, "ui.cursor.match"),
+    (&["CursorLine"], Attributes::Bg, "ui.cursorline.primary"),
+    (&["ColorColumn"], Attributes::Bg, "ui.virtual.ruler"),
+    (&["LineNr"], Attributes::All, "ui.linenr"),
+    (&["CursorLineNr"], Attributes::All, "ui.linenr.selected"),
+    (&["StatusLine"], Attributes::All, "ui.statusline"),
+    (&["StatusLineNC"], Attributes::All, "ui.statusline.inactive"),
+    (&["TabLine"], Attributes::All, "ui.bufferline"),
+    (&["TabLineSel"], Attributes::All, "ui.bufferline.active"),
+    (
+        &["TabLineFill"],
+        Attributes::All,
+        "ui.bufferline.background",
+    ),
+    (&["Visual"], Attributes::All, "ui.selection"),
+    (&["WinSeparator", "VertSplit"], Attributes::Fg, "ui.window"),
+    (&["Whitespace"], Attributes::Fg, "ui.virtual.whitespace"),
+    (
+        &["IndentBlanklineChar", "IblIndent"],
+        Attributes::Fg,
+        "ui.virtual.indent-guide",
+    ),
+    (&["LspInlayHint"], Attributes::All, "ui.virtual.inlay-hint"),
+    (&["DiagnosticError"], Attributes::Fg, "error"),
+    (&["DiagnosticWarn"], Attributes::Fg, "warning"),
+    (&["DiagnosticInfo"], Attributes::Fg, "info"),
+    (&["DiagnosticHint"], Attributes::Fg, "hint"),
+    (
+        &["DiagnosticUnderlineError"],
+        Attributes::All,
+        "diagnostic.error",
+    ),
+    (
+        &["DiagnosticUnderlineWarn"],
+        Attributes::All,
+        "diagnostic.warning",
+    ),
+    (
+        &["DiagnosticUnderlineInfo"],
+        Attributes::All,
+        "diagnostic.info",
+    ),
+    (
+        &["DiagnosticUnderlineHint"],
+        Attributes::All,
+        "diagnostic.hint",
+    ),
+    (&["GitSignsAdd", "diffAdded"], Attributes::Fg, "diff.plus"),
+    (
+        &["GitSignsChange", "diffChanged"],
+        Attributes::Fg,
+        "diff.delta",
+    ),
+    (
+        &["GitSignsDelete", "diffRemoved"],
+        Attributes::Fg,
+        "diff.minus",
+    ),
+];
+
+/// A Helix theme, to be placed in `~/.config/helix/themes`.
+pub struct Helix<'a> {
+    pub template: &'a Template,
+}
+
+impl Helix<'_> {
+    /// All scopes of the theme; captures override legacy groups, which override UI groups.
+    fn scopes(&self) -> LinkedHashMap<String, HelixStyle<'_>> {
+        let sections = &self.template.sections;
+        let mut scopes = LinkedHashMap::new();
+
+        for (hl_groups, attributes, scope) in UI_SCOPES {
+            if let Some(format) = hl_groups.iter().find_map(|group| sections.resolve(group)) {
+                scopes.insert(scope.to_string(), HelixStyle::new(format, *attributes));
+            }
+        }
+
+        for (hl_group, scope) in LEGACY_GROUPS {
+            if let Some(format) = sections.resolve(hl_group) {
+                scopes.insert(scope.to_string(), HelixStyle::new(format, Attributes::All));
+            }
+        }
+
+        for section in sections.0.values() {
+            for hl_group in section.0.keys() {
+                let Some(capture) = hl_group.strip_prefix('@') else {
+                    continue;
+                };
+                if capture.starts_with("lsp.") {
+                    continue;
+                }
+                if let Some(format) = sections.resolve(hl_group) {
+                    scopes.insert(
+                        helix_scope(capture),
+                        HelixStyle::new(format, Attributes::All),
+                    );
+                }
+            }
+        }
+
+        scopes
+    }
+}
+
+/// Renames a Treesitter capture to its Helix scope using [`CAPTURE_RENAMES`].
+fn helix_scope(capture: &str) -> String {
+    CAPTURE_RENAMES
+        .iter()
+        .find_map(|(from, to)| {
+            let rest = capture.strip_prefix(from)?;
+            (rest.is_empty() || rest.starts_with('.')).then(|| format!("{to}{rest}"))
+        })
+        .unwrap_or_else(|| capture.to_string())
+}
+
+impl Display for Helix<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let information = &self.template.information;
+        writeln!(f, "# {} by {}", information.name, information.author)?;
+        writeln!(f)?;
+
+        for (scope, style) in self.scopes() {
+            writeln!(f, "{} = {style}", toml_string(&scope))?;
+        }
+
+        writeln!(f, "\n[palette]")?;
+        for (name, color) in &self.template.palette.0 {
+            writeln!(f, "{} = \"{color}\"", Key::new(name.as_str()))?;
+        }
+
+        Ok(())
+    }
+}
+
+struct HelixStyle<'a> {
+    fg: Option<&'a Color>,
+    bg: Option<&'a Color>,
+    special: Option<&'a Color>,
+    style: Style,
+}
+
+impl<'a> HelixStyle<'a> {
+    fn new(format: &'a ColorFormat, attributes: Attributes) -> Self {
+        let style = HelixStyle {
+            fg: format.fg.as_ref(),
+            bg: format.bg.as_ref(),
+            special: format.special.as_ref(),
+            style: format.style.unwrap_or_else(Style::empty),
+        };
+        match attributes {
+            Attributes::All => style,
+            Attributes::Fg => HelixStyle { bg: None, ..style },
+            Attributes::Bg => HelixStyle {
+                fg: None,
+                bg: style.bg,
+                special: None,
+                style: Style::empty(),
+            },
+        }
+    }
+}
+
+impl Display for HelixStyle<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut attributes = Vec::new();
+        if let Some(fg) = self.fg {
+            attributes.push(format!("fg = {}", toml_string(&SpecColor(fg).to_string())));
+        }
+        if let Some(bg) = self.bg {
+            attributes.push(format!("bg = {}", toml_string(&SpecColor(bg).to_string())));
+        }
+
+        let modifiers = [
+            (Style::BOLD, "bold"),
+            (Style::ITALIC, "italic"),
+            (Style::REVERSE, "reversed"),
+            (Style::STRIKETHROUGH, "crossed_out"),
+        ]
+        .iter()
+        .filter(|(flag, _)| self.style.contains(*flag))
+        .map(|(_, modifier)| format!("\"{modifier}\""))
+        .collect::<Vec<_>>();
+        if !modifiers.is_empty() {
+            attributes.push(format!("modifiers = [{}]", modifiers.join(", ")));
+        }
+
+        let underline = [
+            (Style::UNDERLINE, "line"),
+            (Style::UNDERCURL, "curl"),
+            (Style::UNDERDOUBLE, "double_line"),
+            (Style::UNDERDOTTED, "dotted"),
+            (Style::UNDERDASHED, "dashed"),
+        ]
+        .iter()
+        .find(|(flag, _)| self.style.contains(*flag));
+        if let Some((_, underline)) = underline {
+            match self.special {
+                Some(special) => attributes.push(format!(
+                    "underline = {{ style = \"{underline}\", color = {} }}",
+                    toml_string(&SpecColor(special).to_string())
+                )),
+                None => attributes.push(format!("underline = {{ style = \"{underline}\" }}")),
+            }
+        }
+
+        if attributes.is_empty() {
+            return write!(f, "{{}}");
+        }
+        write!(f, "{{ {} }}", attributes.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_names_are_valid_keys() {
+        let template: Template = r##"
+[information]
+name = "sample"
+author = "me"
+background = "dark"
+
+[palette]
+bg = "#101010"
+fg = "#c0c0c0"
+"dark.blue" = "#000080"
+"say \"hi\"" = "#808080"
+
+[highlights]
+Normal = "fg bg"
+"##
+        .parse()
+        .unwrap();
+        let theme = Helix {
+            template: &template,
+        }
+        .to_string();
+
+        let theme: toml::Table = theme.parse().unwrap();
+        let palette = theme["palette"].as_table().unwrap();
+        assert_eq!(palette["dark.blue"].as_str(), Some("#000080"));
+        assert_eq!(palette["say \"hi\""].as_str(), Some("#808080"));
+        assert_eq!(theme["ui.text"]["fg"].as_str(), Some("fg"));
+    }
+}
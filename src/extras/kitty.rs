@@ -0,0 +1,36 @@
+use crate::extras::TerminalTheme;
+use std::fmt::{self, Display, Formatter};
+
+/// A kitty theme, to be included from `kitty.conf`.
+pub struct Kitty<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for Kitty<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        writeln!(f, "## name: {}", theme.name)?;
+        writeln!(f, "## author: {}", theme.author)?;
+
+        writeln!(f, "\nforeground {}", theme.foreground)?;
+        writeln!(f, "background {}", theme.background)?;
+
+        if let Some((cursor, text)) = theme.cursor {
+            writeln!(f, "cursor {cursor}")?;
+            writeln!(f, "cursor_text_color {text}")?;
+        }
+
+        if let Some((background, foreground)) = theme.selection {
+            writeln!(f, "selection_background {background}")?;
+            match foreground {
+                Some(foreground) => writeln!(f, "selection_foreground {foreground}")?,
+                None => writeln!(f, "selection_foreground none")?,
+            }
+        }
+
+        writeln!(f)?;
+        for (index, color) in theme.ansi.iter().enumerate() {
+            writeln!(f, "color{index} {color}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    palette::RgbColor,
+    sections::color_spec::{ColorFormat, Style},
+    Template,
+};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The `[textmate]` section, mapping highlight groups to TextMate scopes.
+///
+/// The scopes listed here are taken out of the default mapping ([`TOKEN_SCOPES`]).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TextMateSection(pub LinkedHashMap<String, TextMateScopes>);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TextMateScopes {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl TextMateScopes {
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let scopes = match self {
+            TextMateScopes::One(scope) => std::slice::from_ref(scope),
+            TextMateScopes::Many(scopes) => scopes.as_slice(),
+        };
+        scopes.iter().map(String::as_str)
+    }
+}
+
+/// The default TextMate scopes and the highlight groups they are colored with, the first group
+/// that is defined wins.
+const TOKEN_SCOPES: &[(&[&str], &[&str])] = &[
+    (
+        &["@comment", "TSComment", "Comment"],
+        &["comment", "punctuation.definition.comment"],
+    ),
+    (&["@string", "TSString", "String"], &["string"]),
+    (
+        &["@string.regexp", "@string.regex", "TSStringRegex"],
+        &["string.regexp"],
+    ),
+    (
+        &["@string.escape", "TSStringEscape", "SpecialChar"],
+        &["constant.character.escape"],
+    ),
+    (
+        &["@character", "TSCharacter", "Character"],
+        &["constant.character"],
+    ),
+    (&["@number", "TSNumber", "Number"], &["constant.numeric"]),
+    (
+        &["@boolean", "TSBoolean", "Boolean"],
+        &["constant.language.boolean"],
+    ),
+    (
+        &["@constant", "TSConstant", "Constant"],
+        &["constant", "variable.other.constant"],
+    ),
+    (
+        &["@constant.builtin", "TSConstBuiltin"],
+        &["constant.language"],
+    ),
+    (
+        &["@keyword", "TSKeyword", "Keyword"],
+        &["keyword", "storage.type", "storage.modifier"],
+    ),
+    (
+        &["@keyword.function", "TSKeywordFunction"],
+        &["keyword.function", "storage.type.function"],
+    ),
+    (
+        &["@keyword.return", "TSKeywordReturn"],
+        &["keyword.control.return"],
+    ),
+    (
+        &[
+            "@keyword.conditional",
+            "@conditional",
+            "TSConditional",
+            "Conditional",
+        ],
+        &["keyword.control.conditional"],
+    ),
+    (
+        &["@keyword.repeat", "@repeat", "TSRepeat", "Repeat"],
+        &["keyword.control.loop"],
+    ),
+    (
+        &["@keyword.import", "@include", "TSInclude", "Include"],
+        &["keyword.control.import", "keyword.control.include"],
+    ),
+    (
+        &["@operator", "TSOperator", "Operator"],
+        &["keyword.operator"],
+    ),
+    (
+        &["@function", "TSFunction", "Function"],
+        &["entity.name.function", "support.function"],
+    ),
+    (
+        &["@function.builtin", "TSFuncBuiltin"],
+        &["support.function.builtin"],
+    ),
+    (
+        &["@function.method", "@method", "TSMethod"],
+        &["entity.name.function.member", "entity.name.function.method"],
+    ),
+    (
+        &["@function.macro", "TSFuncMacro", "Macro"],
+        &["entity.name.function.macro", "support.macro"],
+    ),
+    (
+        &["@constructor", "TSConstructor"],
+        &["entity.name.function.constructor"],
+    ),
+    (
+        &["@type", "TSType", "Type"],
+        &["entity.name.type", "support.type", "entity.name.class"],
+    ),
+    (
+        &["@type.builtin", "TSTypeBuiltin"],
+        &["support.type.primitive", "storage.type.primitive"],
+    ),
+    (
+        &["@variable", "TSVariable", "Identifier"],
+        &["variable", "meta.definition.variable.name"],
+    ),
+    (
+        &["@variable.builtin", "TSVariableBuiltin"],
+        &["variable.language"],
+    ),
+    (
+        &["@variable.parameter", "@parameter", "TSParameter"],
+        &["variable.parameter"],
+    ),
+    (
+        &["@property", "@field", "TSProperty", "TSField"],
+        &["variable.other.property", "variable.other.object.property"],
+    ),
+    (
+        &["@module", "@namespace", "TSNamespace"],
+        &["entity.name.namespace", "entity.name.type.module"],
+    ),
+    (&["@label", "TSLabel", "Label"], &["entity.name.label"]),
+    (
+        &["@punctuation.delimiter", "TSPunctDelimiter", "Delimiter"],
+        &["punctuation.separator", "punctuation.terminator"],
+    ),
+    (
+        &["@punctuation.bracket", "TSPunctBracket"],
+        &["punctuation.bracket", "meta.brace"],
+    ),
+    (&["@tag", "TSTag", "Tag"], &["entity.name.tag"]),
+    (
+        &["@tag.attribute", "TSTagAttribute"],
+        &["entity.other.attribute-name"],
+    ),
+    (
+        &["@markup.heading", "@text.title", "TSTitle", "Title"],
+        &["markup.heading", "entity.name.section"],
+    ),
+    (
+        &["@markup.strong", "@text.strong", "TSStrong"],
+        &["markup.bold"],
+    ),
+    (
+        &["@markup.italic", "@text.emphasis", "TSEmphasis"],
+        &["markup.italic"],
+    ),
+    (
+        &["@markup.link.url", "@text.uri", "TSURI"],
+        &["markup.underline.link"],
+    ),
+    (
+        &["@markup.raw", "@text.literal", "TSLiteral"],
+        &["markup.inline.raw", "markup.fenced_code"],
+    ),
+    (&["@markup.quote"], &["markup.quote"]),
+    (
+        &["@diff.plus", "diffAdded", "GitSignsAdd"],
+        &["markup.inserted"],
+    ),
+    (
+        &["@diff.minus", "diffRemoved", "GitSignsDelete"],
+        &["markup.deleted"],
+    ),
+    (
+        &["@diff.delta", "diffChanged", "GitSignsChange"],
+        &["markup.changed"],
+    ),
+];
+
+/// The colors of a set of TextMate scopes.
+#[derive(Debug)]
+pub struct TokenRule<'a> {
+    /// The highlight group the colors are taken from.
+    pub name: &'a str,
+    pub scopes: Vec<&'a str>,
+    pub foreground: Option<RgbColor>,
+    pub background: Option<RgbColor>,
+    pub font_style: String,
+}
+
+/// Resolves the default scopes together with the ones of the `[textmate]` section.
+pub fn token_rules(template: &Template) -> Vec<TokenRule<'_>> {
+    let sections = &template.sections;
+    let custom = template.textmate.as_ref();
+    let is_custom = |scope: &str| {
+        custom.is_some_and(|custom| {
+            custom
+                .0
+                .values()
+                .any(|scopes| scopes.iter().any(|s| s == scope))
+        })
+    };
+
+    let defaults = TOKEN_SCOPES.iter().filter_map(|(hl_groups, scopes)| {
+        let (hl_group, format) = hl_groups
+            .iter()
+            .find_map(|hl_group| Some((*hl_group, sections.resolve(hl_group)?)))?;
+        let scopes = scopes
+            .iter()
+            .copied()
+            .filter(|scope| !is_custom(scope))
+            .collect::<Vec<_>>();
+        (!scopes.is_empty()).then(|| TokenRule::new(template, hl_group, scopes, format))
+    });
+
+    let custom =
+        custom
+            .into_iter()
+            .flat_map(|custom| &custom.0)
+            .filter_map(|(hl_group, scopes)| {
+                let format = sections.resolve(hl_group)?;
+                Some(TokenRule::new(
+                    template,
+                    hl_group,
+                    scopes.iter().collect(),
+                    format,
+                ))
+            });
+
+    defaults.chain(custom).collect()
+}
+
+impl<'a> TokenRule<'a> {
+    fn new(template: &Template, name: &'a str, scopes: Vec<&'a str>, format: &ColorFormat) -> Self {
+        let palette = &template.palette;
+        TokenRule {
+            name,
+            scopes,
+            foreground: format.fg.as_ref().and_then(|fg| fg.resolve(palette)),
+            background: format.bg.as_ref().and_then(|bg| bg.resolve(palette)),
+            font_style: font_style(format.style),
+        }
+    }
+}
+
+/// The space separated `fontStyle` of TextMate themes.
+pub fn font_style(style: Option<Style>) -> String {
+    let style = style.unwrap_or_else(Style::empty);
+    [
+        (Style::ITALIC, "italic"),
+        (Style::BOLD, "bold"),
+        (Style::UNDERLINE, "underline"),
+        (Style::STRIKETHROUGH, "strikethrough"),
+    ]
+    .iter()
+    .filter(|(flag, _)| style.contains(*flag))
+    .map(|(_, name)| *name)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// The editor colors of a `.tmTheme` and the highlight groups they are taken from.
+const GLOBAL_SETTINGS: &[(&str, &str, bool)] = &[
+    ("Normal", "foreground", false),
+    ("Normal", "background", true),
+    ("Cursor", "caret", true),
+    ("CursorLine", "lineHighlight", true),
+    ("Visual", "selection", true),
+    ("Search", "findHighlight", true),
+    ("LineNr", "gutterForeground", false),
+    ("SignColumn", "gutter", true),
+    ("Whitespace", "invisibles", false),
+    ("MatchParen", "bracketsForeground", false),
+];
+
+/// A TextMate theme for bat, delta and Sublime Text.
+pub struct TmTheme<'a> {
+    pub template: &'a Template,
+}
+
+impl Display for TmTheme<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let information = &self.template.information;
+        let palette = &self.template.palette;
+
+        writeln!(
+            f,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>name</key>
+  <string>{name}</string>
+  <key>author</key>
+  <string>{author}</string>
+  <key>settings</key>
+  <array>
+    <dict>
+      <key>settings</key>
+      <dict>"#,
+            name = Xml(&information.name),
+            author = Xml(&information.author),
+        )?;
+
+        for (hl_group, key, background) in GLOBAL_SETTINGS {
+            let Some(format) = self.template.sections.resolve(hl_group) else {
+                continue;
+            };
+            let color = if *background { &format.bg } else { &format.fg };
+            if let Some(color) = color.as_ref().and_then(|color| color.resolve(palette)) {
+                writeln!(
+                    f,
+                    "        <key>{key}</key>\n        <string>{color}</string>"
+                )?;
+            }
+        }
+        writeln!(f, "      </dict>\n    </dict>")?;
+
+        for rule in token_rules(self.template) {
+            writeln!(f, "    <dict>")?;
+            writeln!(
+                f,
+                "      <key>name</key>\n      <string>{}</string>",
+                Xml(rule.name)
+            )?;
+            writeln!(
+                f,
+                "      <key>scope</key>\n      <string>{}</string>",
+                Xml(&rule.scopes.join(", "))
+            )?;
+            writeln!(f, "      <key>settings</key>\n      <dict>")?;
+            if let Some(foreground) = rule.foreground {
+                writeln!(
+                    f,
+                    "        <key>foreground</key>\n        <string>{foreground}</string>"
+                )?;
+            }
+            if let Some(background) = rule.background {
+                writeln!(
+                    f,
+                    "        <key>background</key>\n        <string>{background}</string>"
+                )?;
+            }
+            if !rule.font_style.is_empty() {
+                writeln!(
+                    f,
+                    "        <key>fontStyle</key>\n        <string>{}</string>",
+                    rule.font_style
+                )?;
+            }
+            writeln!(f, "      </dict>\n    </dict>")?;
+        }
+
+        writeln!(f, "  </array>\n</dict>\n</plist>")
+    }
+}
+
+/// Escapes text for XML.
+pub(crate) struct Xml<'a>(pub &'a str);
+
+impl Display for Xml<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => write!(f, "&amp;")?,
+                '<' => write!(f, "&lt;")?,
+                '>' => write!(f, "&gt;")?,
+                '"' => write!(f, "&quot;")?,
+                '\'' => write!(f, "&apos;")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    palette::Palette,
+    sections::{
+        check_palette_ref,
+        color_spec::{ColorFormat, ColorSpec, Style},
+        MissingPaletteColor,
+    },
+    Template,
+};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The `[tmux]` section, overriding the colors of tmux styles.
+///
+/// Styles that aren't set use the highlight groups of [`TmuxStyle::default_links`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TmuxSection(pub LinkedHashMap<TmuxStyle, ColorSpec>);
+
+impl TmuxSection {
+    /// Checks that every palette color of the styles exists.
+    pub fn check_colors(&self, palette: &Palette) -> Result<(), MissingPaletteColor> {
+        for (style, color_spec) in &self.0 {
+            if let ColorSpec::Color(ColorFormat {
+                fg, bg, special, ..
+            }) = color_spec
+            {
+                check_palette_ref(fg, palette, "tmux", style.name())?;
+                check_palette_ref(bg, palette, "tmux", style.name())?;
+                check_palette_ref(special, palette, "tmux", style.name())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TmuxStyle {
+    Status,
+    WindowStatus,
+    WindowStatusCurrent,
+    PaneBorder,
+    PaneActiveBorder,
+    Message,
+    MessageCommand,
+    Mode,
+}
+
+impl TmuxStyle {
+    pub const ALL: [TmuxStyle; 8] = [
+        TmuxStyle::Status,
+        TmuxStyle::WindowStatus,
+        TmuxStyle::WindowStatusCurrent,
+        TmuxStyle::PaneBorder,
+        TmuxStyle::PaneActiveBorder,
+        TmuxStyle::Message,
+        TmuxStyle::MessageCommand,
+        TmuxStyle::Mode,
+    ];
+
+    /// The key of the style in `[tmux]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TmuxStyle::Status => "status",
+            TmuxStyle::WindowStatus => "window_status",
+            TmuxStyle::WindowStatusCurrent => "window_status_current",
+            TmuxStyle::PaneBorder => "pane_border",
+            TmuxStyle::PaneActiveBorder => "pane_active_border",
+            TmuxStyle::Message => "message",
+            TmuxStyle::MessageCommand => "message_command",
+            TmuxStyle::Mode => "mode",
+        }
+    }
+
+    /// The name of the tmux option.
+    pub fn option(self) -> &'static str {
+        match self {
+            TmuxStyle::Status => "status-style",
+            TmuxStyle::WindowStatus => "window-status-style",
+            TmuxStyle::WindowStatusCurrent => "window-status-current-style",
+            TmuxStyle::PaneBorder => "pane-border-style",
+            TmuxStyle::PaneActiveBorder => "pane-active-border-style",
+            TmuxStyle::Message => "message-style",
+            TmuxStyle::MessageCommand => "message-command-style",
+            TmuxStyle::Mode => "mode-style",
+        }
+    }
+
+    /// The highlight groups used when the style isn't set in `[tmux]`, the first one that is
+    /// defined wins.
+    pub fn default_links(self) -> &'static [&'static str] {
+        match self {
+            TmuxStyle::Status => &["StatusLine", "Normal"],
+            TmuxStyle::WindowStatus => &["TabLine", "StatusLine"],
+            TmuxStyle::WindowStatusCurrent => &["TabLineSel", "StatusLine"],
+            TmuxStyle::PaneBorder => &["WinSeparator", "VertSplit"],
+            TmuxStyle::PaneActiveBorder => &["FloatBorder", "WinSeparator", "VertSplit"],
+            TmuxStyle::Message => &["MsgArea", "Normal"],
+            TmuxStyle::MessageCommand => &["MsgArea", "Normal"],
+            TmuxStyle::Mode => &["Visual"],
+        }
+    }
+}
+
+/// A snippet for `.tmux.conf`, to be loaded with `source-file`.
+pub struct Tmux<'a> {
+    pub template: &'a Template,
+}
+
+impl Tmux<'_> {
+    fn resolve(&self, style: TmuxStyle) -> Option<&ColorFormat> {
+        let sections = &self.template.sections;
+        match self
+            .template
+            .tmux
+            .as_ref()
+            .and_then(|tmux| tmux.0.get(&style))
+        {
+            Some(ColorSpec::Color(format)) => Some(format),
+            Some(ColorSpec::Link(link)) => sections.resolve(link),
+            None => style
+                .default_links()
+                .iter()
+                .find_map(|hl_group| sections.resolve(hl_group)),
+        }
+    }
+}
+
+impl Display for Tmux<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let information = &self.template.information;
+        writeln!(f, "# {} by {}", information.name, information.author)?;
+
+        for style in TmuxStyle::ALL {
+            let Some(format) = self.resolve(style) else {
+                continue;
+            };
+            let palette = &self.template.palette;
+            let mut attributes = Vec::new();
+            if let Some(fg) = format.fg.as_ref().and_then(|fg| fg.resolve(palette)) {
+                attributes.push(format!("fg={fg}"));
+            }
+            if let Some(bg) = format.bg.as_ref().and_then(|bg| bg.resolve(palette)) {
+                attributes.push(format!("bg={bg}"));
+            }
+            for flag in format.style.unwrap_or_else(Style::empty).iter() {
+                if let Some(attribute) = tmux_attribute(flag) {
+                    attributes.push(attribute.to_string());
+                }
+            }
+            if attributes.is_empty() {
+                attributes.push("default".to_string());
+            }
+
+            writeln!(f, "set -g {} '{}'", style.option(), attributes.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn tmux_attribute(style: Style) -> Option<&'static str> {
+    Some(match style {
+        Style::UNDERLINE => "underscore",
+        Style::UNDERCURL => "curly-underscore",
+        Style::UNDERDOUBLE => "double-underscore",
+        Style::UNDERDOTTED => "dotted-underscore",
+        Style::UNDERDASHED => "dashed-underscore",
+        Style::STRIKETHROUGH => "strikethrough",
+        Style::ITALIC => "italics",
+        Style::BOLD => "bold",
+        Style::REVERSE => "reverse",
+        Style::STANDOUT | Style::NOCOMBINE => return None,
+        _ => unreachable!(),
+    })
+}
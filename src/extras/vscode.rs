@@ -0,0 +1,288 @@
+use crate::{
+    extras::textmate::{font_style, token_rules},
+    palette::RgbColor,
+    sections::color_spec::ColorFormat,
+    Template,
+};
+use serde_json::{json, Map, Value};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Copy)]
+enum Attribute {
+    Fg,
+    Bg,
+}
+
+/// Workbench colors and the highlight groups they are taken from.
+const WORKBENCH_COLORS: &[(&[&str], Attribute, &[&str])] = &[
+    (
+        &["Normal"],
+        Attribute::Bg,
+        &[
+            "editor.background",
+            "panel.background",
+            "terminal.background",
+        ],
+    ),
+    (
+        &["Normal"],
+        Attribute::Fg,
+        &["editor.foreground", "foreground", "terminal.foreground"],
+    ),
+    (
+        &["NvimTreeNormal", "NormalSB", "Normal"],
+        Attribute::Bg,
+        &["sideBar.background", "activityBar.background"],
+    ),
+    (
+        &["NvimTreeNormal", "NormalSB", "Normal"],
+        Attribute::Fg,
+        &["sideBar.foreground"],
+    ),
+    (
+        &["Pmenu", "NormalFloat"],
+        Attribute::Bg,
+        &[
+            "editorSuggestWidget.background",
+            "editorHoverWidget.background",
+            "editorWidget.background",
+            "quickInput.background",
+        ],
+    ),
+    (
+        &["Pmenu", "NormalFloat"],
+        Attribute::Fg,
+        &["editorSuggestWidget.foreground", "editorWidget.foreground"],
+    ),
+    (
+        &["PmenuSel"],
+        Attribute::Bg,
+        &[
+            "editorSuggestWidget.selectedBackground",
+            "list.activeSelectionBackground",
+        ],
+    ),
+    (
+        &["FloatBorder"],
+        Attribute::Fg,
+        &["editorWidget.border", "editorHoverWidget.border"],
+    ),
+    (
+        &["CursorLine"],
+        Attribute::Bg,
+        &["editor.lineHighlightBackground"],
+    ),
+    (&["LineNr"], Attribute::Fg, &["editorLineNumber.foreground"]),
+    (
+        &["CursorLineNr"],
+        Attribute::Fg,
+        &["editorLineNumber.activeForeground"],
+    ),
+    (&["Cursor"], Attribute::Bg, &["editorCursor.foreground"]),
+    (&["Visual"], Attribute::Bg, &["editor.selectionBackground"]),
+    (
+        &["Search"],
+        Attribute::Bg,
+        &["editor.findMatchHighlightBackground"],
+    ),
+    (
+        &["IncSearch"],
+        Attribute::Bg,
+        &["editor.findMatchBackground"],
+    ),
+    (
+        &["MatchParen"],
+        Attribute::Bg,
+        &["editorBracketMatch.background"],
+    ),
+    (
+        &["Whitespace"],
+        Attribute::Fg,
+        &["editorWhitespace.foreground"],
+    ),
+    (&["Folded"], Attribute::Bg, &["editor.foldBackground"]),
+    (
+        &["WinSeparator", "VertSplit"],
+        Attribute::Fg,
+        &["editorGroup.border", "sideBar.border", "panel.border"],
+    ),
+    (&["SignColumn"], Attribute::Bg, &["editorGutter.background"]),
+    (&["StatusLine"], Attribute::Bg, &["statusBar.background"]),
+    (&["StatusLine"], Attribute::Fg, &["statusBar.foreground"]),
+    (&["TabLine"], Attribute::Bg, &["tab.inactiveBackground"]),
+    (&["TabLine"], Attribute::Fg, &["tab.inactiveForeground"]),
+    (&["TabLineSel"], Attribute::Bg, &["tab.activeBackground"]),
+    (&["TabLineSel"], Attribute::Fg, &["tab.activeForeground"]),
+    (
+        &["TabLineFill"],
+        Attribute::Bg,
+        &["editorGroupHeader.tabsBackground"],
+    ),
+    (
+        &["DiagnosticError", "LspDiagnosticsDefaultError"],
+        Attribute::Fg,
+        &["editorError.foreground", "errorForeground"],
+    ),
+    (
+        &["DiagnosticWarn", "LspDiagnosticsDefaultWarning"],
+        Attribute::Fg,
+        &["editorWarning.foreground"],
+    ),
+    (
+        &["DiagnosticInfo", "LspDiagnosticsDefaultInformation"],
+        Attribute::Fg,
+        &["editorInfo.foreground"],
+    ),
+    (
+        &["DiagnosticHint", "LspDiagnosticsDefaultHint"],
+        Attribute::Fg,
+        &["editorHint.foreground"],
+    ),
+    (
+        &["GitSignsAdd"],
+        Attribute::Fg,
+        &["editorGutter.addedBackground"],
+    ),
+    (
+        &["GitSignsChange"],
+        Attribute::Fg,
+        &["editorGutter.modifiedBackground"],
+    ),
+    (
+        &["GitSignsDelete"],
+        Attribute::Fg,
+        &["editorGutter.deletedBackground"],
+    ),
+    (
+        &["DiffAdd"],
+        Attribute::Bg,
+        &["diffEditor.insertedLineBackground"],
+    ),
+    (
+        &["DiffDelete"],
+        Attribute::Bg,
+        &["diffEditor.removedLineBackground"],
+    ),
+];
+
+/// A VS Code color theme, to be listed under `contributes.themes` of an extension.
+pub struct Vscode<'a> {
+    pub template: &'a Template,
+}
+
+impl Vscode<'_> {
+    fn resolve(&self, hl_groups: &[&str]) -> Option<&ColorFormat> {
+        hl_groups
+            .iter()
+            .find_map(|hl_group| self.template.sections.resolve(hl_group))
+    }
+
+    fn color(&self, format: &ColorFormat, attribute: Attribute) -> Option<RgbColor> {
+        let color = match attribute {
+            Attribute::Fg => &format.fg,
+            Attribute::Bg => &format.bg,
+        };
+        color.as_ref()?.resolve(&self.template.palette)
+    }
+
+    fn colors(&self) -> Map<String, Value> {
+        let mut colors = Map::new();
+        for (hl_groups, attribute, keys) in WORKBENCH_COLORS {
+            let Some(color) = self
+                .resolve(hl_groups)
+                .and_then(|format| self.color(format, *attribute))
+            else {
+                continue;
+            };
+            for key in *keys {
+                colors.insert(key.to_string(), json!(color));
+            }
+        }
+        colors
+    }
+
+    fn token_colors(&self) -> Vec<Value> {
+        token_rules(self.template)
+            .into_iter()
+            .filter_map(|rule| {
+                let mut settings = Map::new();
+                if let Some(foreground) = rule.foreground {
+                    settings.insert("foreground".into(), json!(foreground));
+                }
+                if !rule.font_style.is_empty() {
+                    settings.insert("fontStyle".into(), json!(rule.font_style));
+                }
+                (!settings.is_empty()).then(|| {
+                    json!({
+                        "name": rule.name,
+                        "scope": rule.scopes,
+                        "settings": settings,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Maps `@lsp.type.<type>`, `@lsp.mod.<modifier>` and `@lsp.typemod.<type>.<modifier>` to
+    /// VS Code's semantic token selectors.
+    fn semantic_token_colors(&self) -> Map<String, Value> {
+        let mut tokens = Map::new();
+        for section in self.template.sections.0.values() {
+            for hl_group in section.0.keys() {
+                let selector = if let Some(token) = hl_group.strip_prefix("@lsp.type.") {
+                    token.to_string()
+                } else if let Some(modifier) = hl_group.strip_prefix("@lsp.mod.") {
+                    format!("*.{modifier}")
+                } else if let Some(typemod) = hl_group.strip_prefix("@lsp.typemod.") {
+                    typemod.to_string()
+                } else {
+                    continue;
+                };
+                let Some(settings) = self
+                    .template
+                    .sections
+                    .resolve(hl_group)
+                    .and_then(|format| self.settings(format))
+                else {
+                    continue;
+                };
+                tokens.insert(selector, settings);
+            }
+        }
+        tokens
+    }
+
+    /// The `foreground`/`fontStyle` settings of a token, `None` if it sets neither.
+    fn settings(&self, format: &ColorFormat) -> Option<Value> {
+        let mut settings = Map::new();
+        if let Some(fg) = self.color(format, Attribute::Fg) {
+            settings.insert("foreground".into(), json!(fg));
+        }
+        let font_style = font_style(format.style);
+        if !font_style.is_empty() {
+            settings.insert("fontStyle".into(), json!(font_style));
+        }
+        (!settings.is_empty()).then_some(Value::Object(settings))
+    }
+}
+
+impl Display for Vscode<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let information = &self.template.information;
+        let mut theme = Map::new();
+        theme.insert("$schema".into(), json!("vscode://schemas/color-theme"));
+        theme.insert("name".into(), json!(information.name));
+        theme.insert("type".into(), json!(information.background.to_string()));
+        theme.insert("colors".into(), Value::Object(self.colors()));
+        theme.insert("tokenColors".into(), Value::Array(self.token_colors()));
+
+        let semantic_tokens = self.semantic_token_colors();
+        if !semantic_tokens.is_empty() {
+            theme.insert("semanticHighlighting".into(), json!(true));
+            theme.insert("semanticTokenColors".into(), Value::Object(semantic_tokens));
+        }
+
+        let json = serde_json::to_string_pretty(&Value::Object(theme)).map_err(|_| fmt::Error)?;
+        writeln!(f, "{json}")
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{extras::TerminalTheme, import::toml_string, palette::RgbColor};
+use std::fmt::{self, Display, Formatter};
+
+/// A WezTerm color scheme, to be placed in one of the `color_scheme_dirs`.
+pub struct Wezterm<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for Wezterm<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        writeln!(f, "[colors]")?;
+        writeln!(f, "foreground = '{}'", theme.foreground)?;
+        writeln!(f, "background = '{}'", theme.background)?;
+
+        if let Some((cursor, text)) = theme.cursor {
+            writeln!(f, "cursor_bg = '{cursor}'")?;
+            writeln!(f, "cursor_border = '{cursor}'")?;
+            writeln!(f, "cursor_fg = '{text}'")?;
+        }
+
+        if let Some((background, foreground)) = theme.selection {
+            writeln!(f, "selection_bg = '{background}'")?;
+            if let Some(foreground) = foreground {
+                writeln!(f, "selection_fg = '{foreground}'")?;
+            }
+        }
+
+        writeln!(f, "ansi = [{}]", ColorList(&theme.ansi[..8]))?;
+        writeln!(f, "brights = [{}]", ColorList(&theme.ansi[8..]))?;
+
+        writeln!(f, "\n[metadata]")?;
+        writeln!(f, "name = {}", toml_string(theme.name))?;
+        writeln!(f, "author = {}", toml_string(theme.author))?;
+
+        Ok(())
+    }
+}
+
+struct ColorList<'a>(&'a [RgbColor]);
+
+impl Display for ColorList<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, color) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "'{color}'")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::extras::TerminalTheme;
+use serde_json::{json, Map, Value};
+use std::fmt::{self, Display, Formatter};
+
+const NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "purple",
+    "cyan",
+    "white",
+    "brightBlack",
+    "brightRed",
+    "brightGreen",
+    "brightYellow",
+    "brightBlue",
+    "brightPurple",
+    "brightCyan",
+    "brightWhite",
+];
+
+/// A Windows Terminal color scheme, to be added to the `schemes` of `settings.json`.
+pub struct WindowsTerminal<'a>(pub &'a TerminalTheme<'a>);
+
+impl Display for WindowsTerminal<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let theme = self.0;
+        let mut scheme = Map::new();
+        scheme.insert("name".into(), json!(theme.name));
+        scheme.insert("foreground".into(), json!(theme.foreground));
+        scheme.insert("background".into(), json!(theme.background));
+
+        if let Some((cursor, _)) = theme.cursor {
+            scheme.insert("cursorColor".into(), json!(cursor));
+        }
+        if let Some((background, _)) = theme.selection {
+            scheme.insert("selectionBackground".into(), json!(background));
+        }
+        for (name, color) in NAMES.iter().zip(theme.ansi) {
+            scheme.insert(name.to_string(), json!(color));
+        }
+
+        let json = serde_json::to_string_pretty(&Value::Object(scheme)).map_err(|_| fmt::Error)?;
+        writeln!(f, "{json}")
+    }
+}
@@ -4,12 +4,14 @@ use std::fmt::{self, Display, Formatter};
 pub struct InitLua<'a> {
     pub name: &'a str,
     pub background: Background,
+    pub termguicolors: bool,
 }
 
 impl<'a> Display for InitLua<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let name = &self.name;
         let background = self.background;
+        let termguicolors = self.termguicolors;
 
         writeln!(
             f,
@@ -26,6 +28,7 @@ return M"#,
             setup = InitSetup {
                 name,
                 background,
+                termguicolors,
                 indent: "  "
             }
         )
@@ -35,6 +38,8 @@ return M"#,
 pub struct InitSetup<'a> {
     pub name: &'a str,
     pub background: Background,
+    /// Force `termguicolors` on, otherwise it is left to the user's configuration.
+    pub termguicolors: bool,
     pub indent: &'a str,
 }
 
@@ -49,11 +54,24 @@ impl Display for InitSetup<'_> {
 {indent}  vim.cmd('syntax reset')
 {indent}end
 
-{indent}vim.o.termguicolors = true
-{indent}vim.g.colors_name = '{name}'"#,
+"#,
             indent = self.indent,
-            name = self.name,
             background = self.background
+        )?;
+
+        if self.termguicolors {
+            writeln!(
+                f,
+                "{indent}vim.o.termguicolors = true",
+                indent = self.indent
+            )?;
+        }
+
+        write!(
+            f,
+            "{indent}vim.g.colors_name = '{name}'",
+            indent = self.indent,
+            name = self.name
         )
     }
 }
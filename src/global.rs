@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct Global(LinkedHashMap<String, String>);
+pub struct Global(pub LinkedHashMap<String, String>);
 
 impl Display for Global {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,38 @@
+use crate::canonicalize::CanonicalizeError;
+
+pub mod base16;
+pub mod dump;
+pub mod highlights;
+pub mod lua;
+pub mod vim;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Toml(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Canonicalize(#[from] CanonicalizeError),
+    #[error("The scheme doesn't define {0}")]
+    MissingColor(&'static str),
+    #[error("Invalid color {value:?} for {name}")]
+    InvalidColor { name: String, value: String },
+}
+
+/// Turns a scheme name into a colorscheme name, e.g. `Gruvbox dark, hard` into
+/// `gruvbox-dark-hard`.
+pub(crate) fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Formats a string as a TOML basic string.
+pub(crate) fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
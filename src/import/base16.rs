@@ -0,0 +1,177 @@
+use crate::{
+    canonicalize::{canonicalize, CanonicalizeOptions},
+    import::{slugify, toml_string, ImportError},
+    information::Background,
+    palette::{Oklab, RgbColor},
+    terminal::AnsiColor,
+};
+use linked_hash_map::LinkedHashMap;
+use serde::Deserialize;
+use std::fmt::{self, Display, Formatter};
+
+pub const BASE16_SLOTS: [&str; 16] = [
+    "base00", "base01", "base02", "base03", "base04", "base05", "base06", "base07", "base08",
+    "base09", "base0A", "base0B", "base0C", "base0D", "base0E", "base0F",
+];
+
+/// The extra slots of base24: two darker backgrounds and the bright ANSI colors.
+pub const BASE24_SLOTS: [&str; 8] = [
+    "base10", "base11", "base12", "base13", "base14", "base15", "base16", "base17",
+];
+
+/// The highlight groups of an imported scheme, only referencing the base16 slots.
+const SECTIONS: &str = include_str!("base16.toml");
+
+/// A base16 or base24 scheme, in the legacy format (colors at the top level) or the one of
+/// tinted-theming (colors under `palette`).
+#[derive(Debug, Deserialize)]
+pub struct Base16Scheme {
+    #[serde(alias = "scheme")]
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    pub slug: Option<String>,
+    pub variant: Option<Background>,
+    pub palette: Option<LinkedHashMap<String, String>>,
+    #[serde(flatten)]
+    pub rest: LinkedHashMap<String, serde_yaml::Value>,
+}
+
+impl Base16Scheme {
+    pub fn from_yaml(input: &str) -> Result<Self, ImportError> {
+        Ok(serde_yaml::from_str(input)?)
+    }
+
+    fn lookup(&self, slot: &str) -> Option<&str> {
+        match &self.palette {
+            Some(palette) => palette
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(slot))
+                .map(|(_, value)| value.as_str()),
+            None => self
+                .rest
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(slot))
+                .and_then(|(_, value)| value.as_str()),
+        }
+    }
+
+    fn color(&self, slot: &'static str) -> Result<Option<RgbColor>, ImportError> {
+        let Some(value) = self.lookup(slot) else {
+            return Ok(None);
+        };
+        let hex = value.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        format!("#{hex}")
+            .parse()
+            .map(Some)
+            .map_err(|_| ImportError::InvalidColor {
+                name: slot.to_string(),
+                value: value.to_string(),
+            })
+    }
+
+    /// The colors of the scheme by slot, the base24 slots are only included if all of them
+    /// are defined.
+    pub fn colors(&self) -> Result<LinkedHashMap<&'static str, RgbColor>, ImportError> {
+        let mut colors = LinkedHashMap::new();
+        for slot in BASE16_SLOTS {
+            let color = self.color(slot)?.ok_or(ImportError::MissingColor(slot))?;
+            colors.insert(slot, color);
+        }
+
+        let base24 = BASE24_SLOTS
+            .iter()
+            .map(|slot| Ok(self.color(slot)?.map(|color| (*slot, color))))
+            .collect::<Result<Option<Vec<_>>, ImportError>>()?;
+        colors.extend(base24.into_iter().flatten());
+
+        Ok(colors)
+    }
+
+    /// Converts the scheme into a formatted template.
+    pub fn to_template(&self) -> Result<String, ImportError> {
+        let colors = self.colors()?;
+        let template = Base16Template {
+            scheme: self,
+            colors: &colors,
+        };
+        Ok(canonicalize(
+            &template.to_string(),
+            CanonicalizeOptions::default(),
+        )?)
+    }
+}
+
+/// A template for a base16 scheme: the slots as palette, stock highlight groups and the
+/// terminal colors.
+pub struct Base16Template<'a> {
+    pub scheme: &'a Base16Scheme,
+    pub colors: &'a LinkedHashMap<&'static str, RgbColor>,
+}
+
+impl Base16Template<'_> {
+    fn background(&self) -> Background {
+        self.scheme.variant.unwrap_or_else(|| {
+            let background = Oklab::from(self.colors["base00"]);
+            if background.l < 0.5 {
+                Background::Dark
+            } else {
+                Background::Light
+            }
+        })
+    }
+
+    /// The base16 slot of an ANSI color, bright colors use the base24 slots when available.
+    fn terminal_slot(&self, color: AnsiColor) -> &'static str {
+        let base24 = self.colors.contains_key("base12");
+        match color {
+            AnsiColor::Black => "base00",
+            AnsiColor::Red => "base08",
+            AnsiColor::Green => "base0B",
+            AnsiColor::Yellow => "base0A",
+            AnsiColor::Blue => "base0D",
+            AnsiColor::Magenta => "base0E",
+            AnsiColor::Cyan => "base0C",
+            AnsiColor::White => "base05",
+            AnsiColor::BrightBlack => "base03",
+            AnsiColor::BrightRed if base24 => "base12",
+            AnsiColor::BrightRed => "base08",
+            AnsiColor::BrightGreen if base24 => "base14",
+            AnsiColor::BrightGreen => "base0B",
+            AnsiColor::BrightYellow if base24 => "base13",
+            AnsiColor::BrightYellow => "base0A",
+            AnsiColor::BrightBlue if base24 => "base16",
+            AnsiColor::BrightBlue => "base0D",
+            AnsiColor::BrightMagenta if base24 => "base17",
+            AnsiColor::BrightMagenta => "base0E",
+            AnsiColor::BrightCyan if base24 => "base15",
+            AnsiColor::BrightCyan => "base0C",
+            AnsiColor::BrightWhite => "base07",
+        }
+    }
+}
+
+impl Display for Base16Template<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let scheme = self.scheme;
+        let name = scheme.slug.clone().unwrap_or_else(|| slugify(&scheme.name));
+
+        writeln!(f, "[information]")?;
+        writeln!(f, "name = {}", toml_string(&name))?;
+        writeln!(f, "background = \"{}\"", self.background())?;
+        writeln!(f, "author = {}", toml_string(&scheme.author))?;
+
+        writeln!(f, "\n[palette]")?;
+        for (slot, color) in self.colors {
+            writeln!(f, "{slot} = \"{color}\"")?;
+        }
+
+        writeln!(f, "\n[terminal]")?;
+        for color in AnsiColor::ALL {
+            writeln!(f, "{} = \"{}\"", color.name(), self.terminal_slot(color))?;
+        }
+
+        write!(f, "\n{SECTIONS}")
+    }
+}
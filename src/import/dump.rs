@@ -0,0 +1,203 @@
+use crate::{
+    import::{
+        highlights::{parse_blend, ImportWarning, ImportedHighlights},
+        vim::parse_attributes,
+        ImportError,
+    },
+    palette::RgbColor,
+    sections::color_spec::{parser::Color, ColorFormat, ColorSpec, Style},
+    vimscript::STYLE_NAMES,
+};
+use serde_json::{Map, Value};
+
+/// Reads highlights captured from a running Neovim, either the output of `:highlight` or
+/// `nvim_get_hl(0, {})` encoded as JSON.
+pub fn import_highlight_dump(input: &str) -> Result<ImportedHighlights, ImportError> {
+    if input.trim_start().starts_with('{') {
+        import_json(input)
+    } else {
+        Ok(import_text(input))
+    }
+}
+
+/// Parses the output of `:highlight`, e.g. `Comment xxx gui=italic guifg=#7c7c7c`.
+///
+/// Long entries continue on indented lines, the `Last set from` lines of `:verbose` are
+/// skipped.
+fn import_text(input: &str) -> ImportedHighlights {
+    let mut imported = ImportedHighlights::default();
+
+    // join continuation lines with the entry they belong to
+    let mut entries: Vec<(usize, String)> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("Last set from") {
+            continue;
+        }
+        match entries.last_mut() {
+            Some((_, entry)) if line.starts_with(char::is_whitespace) => {
+                entry.push(' ');
+                entry.push_str(trimmed);
+            }
+            _ => entries.push((index + 1, trimmed.to_string())),
+        }
+    }
+
+    for (line, entry) in entries {
+        let words = entry.split_whitespace().collect::<Vec<_>>();
+        let (hl_group, color_spec) = match words.as_slice() {
+            [_, "xxx", "cleared"] => continue,
+            [hl_group, "xxx", .., "links", "to", link] => {
+                (hl_group, ColorSpec::Link(link.to_string()))
+            }
+            [hl_group, "xxx", attributes @ ..] => (
+                hl_group,
+                ColorSpec::Color(parse_attributes(
+                    hl_group,
+                    attributes,
+                    &mut imported.warnings,
+                )),
+            ),
+            _ => {
+                imported
+                    .warnings
+                    .push(ImportWarning::Unparsed { line, text: entry });
+                continue;
+            }
+        };
+        imported.highlights.insert(hl_group.to_string(), color_spec);
+    }
+
+    imported
+}
+
+/// Parses the map returned by `nvim_get_hl`, whose colors are integers.
+fn import_json(input: &str) -> Result<ImportedHighlights, ImportError> {
+    let groups: Map<String, Value> = serde_json::from_str(input)?;
+    let mut imported = ImportedHighlights::default();
+
+    for (hl_group, attributes) in groups {
+        let Some(attributes) = attributes.as_object() else {
+            continue;
+        };
+        let color_spec = match attributes.get("link").and_then(Value::as_str) {
+            Some(link) => ColorSpec::Link(link.to_string()),
+            None => ColorSpec::Color(json_attributes(
+                &hl_group,
+                attributes,
+                &mut imported.warnings,
+            )),
+        };
+        imported.highlights.insert(hl_group, color_spec);
+    }
+
+    Ok(imported)
+}
+
+fn json_attributes(
+    hl_group: &str,
+    attributes: &Map<String, Value>,
+    warnings: &mut Vec<ImportWarning>,
+) -> ColorFormat {
+    let color = |key: &str| {
+        let [_, r, g, b] = u32::try_from(attributes.get(key)?.as_u64()?)
+            .ok()?
+            .to_be_bytes();
+        Some(Color::Color(RgbColor([r, g, b])))
+    };
+
+    let style = STYLE_NAMES
+        .iter()
+        .filter(|(name, _)| attributes.get(*name).and_then(Value::as_bool) == Some(true))
+        .map(|(_, style)| *style)
+        .collect::<Style>();
+
+    ColorFormat {
+        fg: color("fg"),
+        bg: color("bg"),
+        style: Some(style).filter(|style| !style.is_empty()),
+        special: color("sp"),
+        blend: attributes
+            .get("blend")
+            .and_then(|blend| parse_blend(hl_group, blend.as_f64(), &blend.to_string(), warnings)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sections::color_spec::parser::SpecString;
+
+    fn spec(imported: &ImportedHighlights, hl_group: &str) -> String {
+        SpecString(&imported.highlights[hl_group]).to_string()
+    }
+
+    #[test]
+    fn highlight_output() {
+        let imported = import_highlight_dump(
+            "\
+Normal         xxx guifg=#c0c0c0 guibg=#101010
+Comment        xxx cterm=italic gui=italic
+                   guifg=#808080
+\tLast set from ~/.config/nvim/colors/sample.lua line 12
+Title          xxx links to Normal
+Conceal        xxx ctermfg=7 guifg=#808080 links to Comment
+Ignore         xxx cleared
+what is this
+",
+        )
+        .unwrap();
+
+        assert_eq!(spec(&imported, "Normal"), "#c0c0c0 #101010");
+        assert_eq!(spec(&imported, "Comment"), "#808080 - i");
+        assert_eq!(spec(&imported, "Title"), "link:Normal");
+        assert_eq!(spec(&imported, "Conceal"), "link:Comment");
+        assert!(!imported.highlights.contains_key("Ignore"));
+        assert!(matches!(
+            imported.warnings.as_slice(),
+            [ImportWarning::Unparsed { line: 8, text }] if text == "what is this"
+        ));
+    }
+
+    #[test]
+    fn nvim_get_hl_json() {
+        let imported = import_highlight_dump(
+            r#"{
+                "Normal": { "fg": 12632256, "bg": 1052688 },
+                "Error": { "fg": 16711680, "sp": 255, "bold": true, "underline": true },
+                "Title": { "link": "Normal" },
+                "Pmenu": { "bg": 1052688, "blend": 10 }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec(&imported, "Normal"), "#c0c0c0 #101010");
+        assert_eq!(spec(&imported, "Error"), "#ff0000 - ub #0000ff");
+        assert_eq!(spec(&imported, "Title"), "link:Normal");
+        assert_eq!(spec(&imported, "Pmenu"), "- #101010 - - 10");
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn json_blend_is_a_percentage() {
+        let imported = import_highlight_dump(
+            r#"{
+                "Full": { "blend": 100 },
+                "Over": { "blend": 200 },
+                "Negative": { "blend": -1 },
+                "Text": { "blend": "50" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec(&imported, "Full"), "- - - - 100");
+        for hl_group in ["Over", "Negative", "Text"] {
+            assert_eq!(spec(&imported, hl_group), "-", "{hl_group}");
+        }
+        assert_eq!(imported.warnings.len(), 3);
+        assert!(imported
+            .warnings
+            .iter()
+            .all(|warning| matches!(warning, ImportWarning::InvalidBlend { .. })));
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    canonicalize::{canonicalize, CanonicalizeOptions},
+    import::ImportError,
+    information::{Background, Information},
+    palette::{Oklab, Palette, RgbColor},
+    sections::{
+        color_spec::{parser::Color, ColorFormat, ColorSpec},
+        Section, Sections,
+    },
+    Template,
+};
+use linked_hash_map::LinkedHashMap;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+/// Highlight groups read from a colorscheme, their colors are still hex values.
+#[derive(Debug, Default)]
+pub struct ImportedHighlights {
+    pub name: Option<String>,
+    pub background: Option<Background>,
+    pub highlights: LinkedHashMap<String, ColorSpec>,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Parts of a colorscheme that couldn't be imported.
+#[derive(Debug, thiserror::Error)]
+pub enum ImportWarning {
+    #[error("{hl_group}.{attribute}: couldn't resolve `{expression}`, it is skipped")]
+    Unresolved {
+        hl_group: String,
+        attribute: String,
+        expression: String,
+    },
+    #[error("line {line}: couldn't parse `{text}`, it is skipped")]
+    Unparsed { line: usize, text: String },
+    #[error("{hl_group}.blend: `{blend}` isn't a percentage between 0 and 100, it is skipped")]
+    InvalidBlend { hl_group: String, blend: String },
+}
+
+/// Keeps a `blend` that Neovim accepts, a whole number between 0 and 100, anything else is
+/// skipped with a warning. `expression` is how the value was written.
+pub(crate) fn parse_blend(
+    hl_group: &str,
+    blend: Option<f64>,
+    expression: &str,
+    warnings: &mut Vec<ImportWarning>,
+) -> Option<u8> {
+    match blend {
+        Some(blend) if (0.0..=100.0).contains(&blend) && blend.fract() == 0.0 => Some(blend as u8),
+        _ => {
+            warnings.push(ImportWarning::InvalidBlend {
+                hl_group: hl_group.to_string(),
+                blend: expression.to_string(),
+            });
+            None
+        }
+    }
+}
+
+impl ImportedHighlights {
+    /// Builds a template, colors closer than `merge_distance` (in Oklab) are merged into one
+    /// palette color.
+    pub fn to_template(self, fallback_name: &str, merge_distance: f64) -> Template {
+        let mut highlights = self.highlights;
+        let palette = cluster_colors(&highlights, merge_distance);
+
+        let normal_bg = match highlights.get("Normal") {
+            Some(ColorSpec::Color(ColorFormat {
+                bg: Some(Color::Color(bg)),
+                ..
+            })) => Some(*bg),
+            _ => None,
+        };
+        let background = self.background.unwrap_or_else(|| match normal_bg {
+            Some(bg) if Oklab::from(bg).l > 0.5 => Background::Light,
+            _ => Background::Dark,
+        });
+
+        let mut sections = LinkedHashMap::new();
+        for (hl_group, mut color_spec) in highlights.drain() {
+            if let ColorSpec::Color(format) = &mut color_spec {
+                for color in [&mut format.fg, &mut format.bg, &mut format.special]
+                    .into_iter()
+                    .flatten()
+                {
+                    if let Color::Color(rgb) = color {
+                        *color = Color::PaletteRef(palette.names[&rgb.0].clone());
+                    }
+                }
+            }
+            sections
+                .entry(section_name(&hl_group).to_string())
+                .or_insert_with(|| Section(LinkedHashMap::new()))
+                .0
+                .insert(hl_group, color_spec);
+        }
+
+        Template {
+            information: Information {
+                name: self.name.unwrap_or_else(|| fallback_name.to_string()),
+                background,
+                author: String::new(),
+                termguicolors: true,
+                cterm: false,
+                modern_captures: false,
+            },
+            palette: palette.palette,
+            global: None,
+            terminal: None,
+            lualine: None,
+            tmux: None,
+            textmate: None,
+            base16: None,
+            check: None,
+            semantic_tokens: None,
+            sections: Sections(sections),
+        }
+    }
+}
+
+/// Writes a template through its serializer, in the canonical style.
+pub fn template_to_string(template: &Template) -> Result<String, ImportError> {
+    Ok(canonicalize(
+        &toml::to_string(template)?,
+        CanonicalizeOptions::default(),
+    )?)
+}
+
+/// The section an imported highlight group is put in.
+fn section_name(hl_group: &str) -> &'static str {
+    if hl_group.starts_with('@') {
+        "treesitter"
+    } else if hl_group.starts_with("Diagnostic") || hl_group.starts_with("Lsp") {
+        "lsp"
+    } else {
+        "highlights"
+    }
+}
+
+struct ClusteredPalette {
+    palette: Palette,
+    /// The palette color every imported color is replaced with.
+    names: HashMap<[u8; 3], String>,
+}
+
+/// Groups the colors of the highlights into palette colors.
+///
+/// The most used colors become the centers of the clusters, every other color joins the first
+/// center closer than `merge_distance`. The colors of `Normal` are named `fg` and `bg`, the
+/// others after their hue and lightness.
+fn cluster_colors(
+    highlights: &LinkedHashMap<String, ColorSpec>,
+    merge_distance: f64,
+) -> ClusteredPalette {
+    let mut counts = LinkedHashMap::<[u8; 3], usize>::new();
+    for color_spec in highlights.values() {
+        let ColorSpec::Color(format) = color_spec else {
+            continue;
+        };
+        for color in [&format.fg, &format.bg, &format.special]
+            .into_iter()
+            .flatten()
+        {
+            if let Color::Color(RgbColor(rgb)) = color {
+                *counts.entry(*rgb).or_default() += 1;
+            }
+        }
+    }
+
+    let mut by_count = counts.iter().collect::<Vec<_>>();
+    by_count.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let mut centers = Vec::<([u8; 3], Oklab)>::new();
+    let mut members = HashMap::new();
+    for (rgb, _) in by_count {
+        let oklab = Oklab::from(RgbColor(*rgb));
+        let center = centers
+            .iter()
+            .find(|(_, center)| center.distance(&oklab) <= merge_distance)
+            .map(|(center, _)| *center);
+        match center {
+            Some(center) => {
+                members.insert(*rgb, center);
+            }
+            None => {
+                centers.push((*rgb, oklab));
+                members.insert(*rgb, *rgb);
+            }
+        }
+    }
+
+    let normal = match highlights.get("Normal") {
+        Some(ColorSpec::Color(format)) => Some(format),
+        _ => None,
+    };
+    let normal_color = |color: Option<&Option<Color>>| match color {
+        Some(Some(Color::Color(RgbColor(rgb)))) => Some(members[rgb]),
+        _ => None,
+    };
+    let fg = normal_color(normal.map(|normal| &normal.fg));
+    let bg = normal_color(normal.map(|normal| &normal.bg));
+
+    // centers in the order they first appear
+    let mut ordered = counts
+        .keys()
+        .map(|rgb| members[rgb])
+        .filter(|rgb| Some(*rgb) != fg && Some(*rgb) != bg)
+        .collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+    ordered.retain(|rgb| seen.insert(*rgb));
+
+    let mut center_names = HashMap::new();
+    let mut palette = LinkedHashMap::new();
+    for (rgb, name) in [(fg, "fg"), (bg, "bg")] {
+        if let Some(rgb) = rgb {
+            if let Entry::Vacant(entry) = center_names.entry(rgb) {
+                entry.insert(name.to_string());
+                palette.insert(name.to_string(), RgbColor(rgb));
+            }
+        }
+    }
+    for rgb in ordered {
+        let base = color_name(RgbColor(rgb));
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while palette.contains_key(&name) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        center_names.insert(rgb, name.clone());
+        palette.insert(name, RgbColor(rgb));
+    }
+
+    let names = members
+        .into_iter()
+        .map(|(rgb, center)| (rgb, center_names[&center].clone()))
+        .collect();
+
+    ClusteredPalette {
+        palette: Palette(palette),
+        names,
+    }
+}
+
+/// A name for a color after its hue and lightness, e.g. `dark_blue` or `light_gray`.
+pub(crate) fn color_name(color: RgbColor) -> String {
+    let Oklab { l, a, b } = Oklab::from(color);
+
+    if a.hypot(b) < 0.03 {
+        let name = match l {
+            l if l < 0.3 => "black",
+            l if l < 0.5 => "dark_gray",
+            l if l < 0.7 => "gray",
+            l if l < 0.9 => "light_gray",
+            _ => "white",
+        };
+        return name.to_string();
+    }
+
+    let hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+    let name = match hue {
+        h if h < 40.0 => "red",
+        h if h < 75.0 => "orange",
+        h if h < 115.0 => "yellow",
+        h if h < 165.0 => "green",
+        h if h < 220.0 => "cyan",
+        h if h < 280.0 => "blue",
+        h if h < 320.0 => "purple",
+        h if h < 355.0 => "magenta",
+        _ => "red",
+    };
+    match l {
+        l if l < 0.5 => format!("dark_{name}"),
+        l if l > 0.8 => format!("light_{name}"),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{import::vim::import_vim, sections::color_spec::parser::SpecString};
+
+    #[test]
+    fn close_colors_are_merged() {
+        let imported = import_vim(
+            "\
+hi Normal guifg=#c0c0c0 guibg=#101010
+hi Error guifg=#ff0000
+hi ErrorMsg guifg=#fe0101
+hi WarningMsg guifg=#fe0101
+hi SpellBad guisp=#fe0101
+hi DiagnosticError guifg=#ff0000 guibg=#111111
+hi @comment guifg=#808080
+",
+        );
+        let template = imported.to_template("sample", 0.02);
+
+        assert_eq!(template.information.name, "sample");
+        assert!(matches!(template.information.background, Background::Dark));
+        let palette = template
+            .palette
+            .0
+            .iter()
+            .map(|(name, color)| (name.as_str(), color.to_string()))
+            .collect::<Vec<_>>();
+        // the most used of the close reds is the center
+        assert_eq!(
+            palette,
+            [
+                ("fg", "#c0c0c0".to_string()),
+                ("bg", "#101010".to_string()),
+                ("red", "#fe0101".to_string()),
+                ("gray", "#808080".to_string()),
+            ]
+        );
+
+        let spec = |section: &str, hl_group: &str| {
+            SpecString(&template.sections.0[section].0[hl_group]).to_string()
+        };
+        assert_eq!(spec("highlights", "Normal"), "fg bg");
+        assert_eq!(spec("highlights", "Error"), "red");
+        assert_eq!(spec("lsp", "DiagnosticError"), "red bg");
+        assert_eq!(spec("treesitter", "@comment"), "gray");
+    }
+
+    #[test]
+    fn blend_range() {
+        let mut warnings = Vec::new();
+        assert_eq!(parse_blend("Pmenu", Some(0.0), "0", &mut warnings), Some(0));
+        assert_eq!(
+            parse_blend("Pmenu", Some(100.0), "100", &mut warnings),
+            Some(100)
+        );
+        assert!(warnings.is_empty());
+
+        for (blend, expression) in [(Some(101.0), "101"), (Some(-1.0), "-1"), (Some(0.5), "0.5")] {
+            assert_eq!(parse_blend("Pmenu", blend, expression, &mut warnings), None);
+        }
+        assert_eq!(parse_blend("Pmenu", None, "s:blend", &mut warnings), None);
+        assert_eq!(
+            warnings.last().unwrap().to_string(),
+            "Pmenu.blend: `s:blend` isn't a percentage between 0 and 100, it is skipped"
+        );
+        assert_eq!(warnings.len(), 4);
+    }
+}
@@ -0,0 +1,371 @@
+use crate::{
+    import::highlights::{parse_blend, ImportWarning, ImportedHighlights},
+    information::Background,
+    palette::RgbColor,
+    sections::color_spec::{parser::Color, ColorFormat, ColorSpec, Style},
+    vimscript::STYLE_NAMES,
+};
+use std::collections::HashMap;
+
+/// Reads the `nvim_set_hl(ns, name, { ... })` calls of a Lua colorscheme.
+///
+/// Attribute values may be literals or variables assigned a hex color anywhere in the file
+/// (`local red = "#ff0000"` or `red = "#ff0000"` in a table, looked up by their last name, the
+/// first assignment wins).
+/// `vim.o.background` and `vim.g.colors_name` are picked up as well.
+pub fn import_lua(input: &str) -> ImportedHighlights {
+    let tokens = tokenize(input);
+    let mut imported = ImportedHighlights::default();
+
+    // variables holding a color or an alias of `nvim_set_hl`
+    let mut colors = HashMap::new();
+    let mut set_hl = vec!["nvim_set_hl".to_string()];
+    for window in tokens.windows(3) {
+        match window {
+            [Token::Name(name), Token::Punct('='), Token::Str(value)] => {
+                if let Ok(color) = value.parse::<RgbColor>() {
+                    colors.entry(last_name(name).to_string()).or_insert(color);
+                }
+                match name.as_str() {
+                    "vim.o.background" | "vim.opt.background" => {
+                        imported.background = match value.as_str() {
+                            "light" => Some(Background::Light),
+                            "dark" => Some(Background::Dark),
+                            _ => imported.background,
+                        }
+                    }
+                    "vim.g.colors_name" => imported.name = Some(value.clone()),
+                    _ => (),
+                }
+            }
+            [Token::Name(name), Token::Punct('='), Token::Name(value)]
+                if last_name(value) == "nvim_set_hl" =>
+            {
+                set_hl.push(name.clone());
+            }
+            _ => (),
+        }
+    }
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let call = match &tokens[index..] {
+            [Token::Name(function), Token::Punct('('), _, Token::Punct(','), Token::Str(hl_group), Token::Punct(','), Token::Punct('{'), ..]
+                if set_hl
+                    .iter()
+                    .any(|name| name == function || name == last_name(function)) =>
+            {
+                Some(hl_group)
+            }
+            _ => None,
+        };
+        let Some(hl_group) = call else {
+            index += 1;
+            continue;
+        };
+
+        index += 7;
+        let (color_spec, end) =
+            parse_attributes(hl_group, &tokens[index..], &colors, &mut imported.warnings);
+        index += end;
+        imported.highlights.insert(hl_group.clone(), color_spec);
+    }
+
+    imported
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier, including its dotted path like `vim.api.nvim_set_hl`.
+    Name(String),
+    Str(String),
+    Number(f64),
+    Punct(char),
+}
+
+fn last_name(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+/// Splits Lua code into the tokens needed to find highlight definitions, comments are dropped.
+fn tokenize(input: &str) -> Vec<Token> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while let Some(&c) = chars.get(index) {
+        let rest = &chars[index..];
+        if c.is_whitespace() {
+            index += 1;
+        } else if rest.starts_with(&['-', '-']) {
+            let long = rest[2..].starts_with(&['[', '[']);
+            let end = if long {
+                find(rest, &[']', ']']).map(|end| end + 2)
+            } else {
+                rest.iter().position(|c| *c == '\n')
+            };
+            index += end.unwrap_or(rest.len());
+        } else if rest.starts_with(&['[', '[']) {
+            let end = find(rest, &[']', ']']).unwrap_or(rest.len());
+            tokens.push(Token::Str(rest[2..end.max(2)].iter().collect()));
+            index += end + 2;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            let mut end = 1;
+            while let Some(&next) = rest.get(end) {
+                end += 1;
+                match next {
+                    '\\' => {
+                        if let Some(&escaped) = rest.get(end) {
+                            value.push(escaped);
+                            end += 1;
+                        }
+                    }
+                    next if next == c => break,
+                    next => value.push(next),
+                }
+            }
+            tokens.push(Token::Str(value));
+            index += end;
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .iter()
+                .position(|c| !(c.is_ascii_alphanumeric() || *c == '.'))
+                .unwrap_or(rest.len());
+            let number = rest[..end].iter().collect::<String>();
+            let value = match number.strip_prefix("0x").or(number.strip_prefix("0X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().map(f64::from),
+                None => number.parse().ok(),
+            };
+            if let Some(value) = value {
+                tokens.push(Token::Number(value));
+            }
+            index += end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .iter()
+                .enumerate()
+                .position(|(i, c)| {
+                    !(c.is_alphanumeric()
+                        || *c == '_'
+                        || ((*c == '.' || *c == ':')
+                            && rest
+                                .get(i + 1)
+                                .is_some_and(|c| c.is_alphabetic() || *c == '_')))
+                })
+                .unwrap_or(rest.len());
+            tokens.push(Token::Name(rest[..end].iter().collect()));
+            index += end;
+        } else {
+            tokens.push(Token::Punct(c));
+            index += 1;
+        }
+    }
+
+    tokens
+}
+
+fn find(haystack: &[char], needle: &[char]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Parses the fields of the attribute table up to its closing brace, returns the color spec and
+/// the number of tokens consumed.
+fn parse_attributes(
+    hl_group: &str,
+    tokens: &[Token],
+    colors: &HashMap<String, RgbColor>,
+    warnings: &mut Vec<ImportWarning>,
+) -> (ColorSpec, usize) {
+    let mut format = ColorFormat {
+        fg: None,
+        bg: None,
+        style: None,
+        special: None,
+        blend: None,
+    };
+    let mut style = Style::empty();
+    let mut link = None;
+
+    let mut index = 0;
+    while index < tokens.len() {
+        let (key, value) = match &tokens[index..] {
+            [Token::Punct('}'), ..] => {
+                index += 1;
+                break;
+            }
+            [Token::Name(key), Token::Punct('='), rest @ ..] => {
+                // the value runs up to the next separator outside of brackets
+                let mut depth = 0;
+                let end = rest
+                    .iter()
+                    .position(|token| {
+                        match token {
+                            Token::Punct('(' | '{' | '[') => depth += 1,
+                            Token::Punct(')' | ']') => depth -= 1,
+                            Token::Punct('}') if depth == 0 => return true,
+                            Token::Punct('}') => depth -= 1,
+                            Token::Punct(',' | ';') if depth == 0 => return true,
+                            _ => (),
+                        }
+                        false
+                    })
+                    .unwrap_or(rest.len());
+                index += 2 + end;
+                (key.as_str(), &rest[..end])
+            }
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+
+        let color = |value: &[Token], warnings: &mut Vec<ImportWarning>| match value {
+            [Token::Str(value)] if value.eq_ignore_ascii_case("none") => None,
+            [Token::Str(value)] => value.parse().ok().map(Color::Color).or_else(|| {
+                warnings.push(unresolved(hl_group, key, value));
+                None
+            }),
+            [Token::Number(value)] => {
+                let value = *value as u32;
+                let [_, r, g, b] = value.to_be_bytes();
+                Some(Color::Color(RgbColor([r, g, b])))
+            }
+            [Token::Name(name)] => colors
+                .get(last_name(name))
+                .copied()
+                .map(Color::Color)
+                .or_else(|| {
+                    warnings.push(unresolved(hl_group, key, name));
+                    None
+                }),
+            value => {
+                warnings.push(unresolved(hl_group, key, &describe(value)));
+                None
+            }
+        };
+
+        match key {
+            "fg" | "foreground" => format.fg = color(value, warnings),
+            "bg" | "background" => format.bg = color(value, warnings),
+            "sp" | "special" => format.special = color(value, warnings),
+            "link" => match value {
+                [Token::Str(value)] => link = Some(value.clone()),
+                value => warnings.push(unresolved(hl_group, key, &describe(value))),
+            },
+            "blend" => {
+                let blend = match value {
+                    [Token::Number(blend)] => Some(*blend),
+                    _ => None,
+                };
+                format.blend = parse_blend(hl_group, blend, &describe(value), warnings);
+            }
+            key => {
+                let key = if key == "inverse" { "reverse" } else { key };
+                if let Some((_, flag)) = STYLE_NAMES.iter().find(|(name, _)| *name == key) {
+                    if value == [Token::Name("true".to_string())] {
+                        style |= *flag;
+                    }
+                }
+            }
+        }
+    }
+
+    format.style = Some(style).filter(|style| !style.is_empty());
+    let color_spec = match link {
+        Some(link) => ColorSpec::Link(link),
+        None => ColorSpec::Color(format),
+    };
+    (color_spec, index)
+}
+
+fn unresolved(hl_group: &str, attribute: &str, expression: &str) -> ImportWarning {
+    ImportWarning::Unresolved {
+        hl_group: hl_group.to_string(),
+        attribute: attribute.to_string(),
+        expression: expression.to_string(),
+    }
+}
+
+/// Writes tokens back as an approximation of the source, for warnings.
+fn describe(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Name(name) => name.clone(),
+            Token::Str(value) => format!("{value:?}"),
+            Token::Number(value) => value.to_string(),
+            Token::Punct(c) => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sections::color_spec::parser::SpecString;
+
+    fn spec(imported: &ImportedHighlights, hl_group: &str) -> String {
+        SpecString(&imported.highlights[hl_group]).to_string()
+    }
+
+    #[test]
+    fn set_hl_attributes_and_aliases() {
+        let imported = import_lua(
+            r##"
+local colors = { red = "#ff0000" }
+local hl = vim.api.nvim_set_hl
+vim.o.background = "light"
+vim.g.colors_name = "sample"
+hl(0, "Normal", { foreground = "#c0c0c0", background = "#101010" })
+hl(0, "Error", { fg = colors.red, bg = 0x202020, sp = "NONE", bold = true, inverse = true })
+vim.api.nvim_set_hl(0, "Title", { link = "Normal" }) -- a comment
+vim.api.nvim_set_hl(0, "Unknown", { fg = palette.missing })
+"##,
+        );
+
+        assert_eq!(imported.name.as_deref(), Some("sample"));
+        assert!(matches!(imported.background, Some(Background::Light)));
+        assert_eq!(spec(&imported, "Normal"), "#c0c0c0 #101010");
+        assert_eq!(spec(&imported, "Error"), "#ff0000 #202020 br");
+        assert_eq!(spec(&imported, "Title"), "link:Normal");
+        assert_eq!(spec(&imported, "Unknown"), "-");
+        assert!(matches!(
+            imported.warnings.as_slice(),
+            [ImportWarning::Unresolved { hl_group, expression, .. }]
+                if hl_group == "Unknown" && expression == "palette.missing"
+        ));
+    }
+
+    #[test]
+    fn blend_is_a_percentage() {
+        let imported = import_lua(
+            r#"
+vim.api.nvim_set_hl(0, "Zero", { blend = 0 })
+vim.api.nvim_set_hl(0, "Full", { blend = 100 })
+vim.api.nvim_set_hl(0, "Over", { blend = 150 })
+vim.api.nvim_set_hl(0, "Wraps", { blend = 256 })
+vim.api.nvim_set_hl(0, "Negative", { blend = -5 })
+vim.api.nvim_set_hl(0, "Fraction", { blend = 2.5 })
+"#,
+        );
+
+        assert_eq!(spec(&imported, "Zero"), "- - - - 0");
+        assert_eq!(spec(&imported, "Full"), "- - - - 100");
+        for hl_group in ["Over", "Wraps", "Negative", "Fraction"] {
+            assert_eq!(spec(&imported, hl_group), "-", "{hl_group}");
+        }
+        let invalid = imported
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                ImportWarning::InvalidBlend { hl_group, .. } => hl_group.as_str(),
+                warning => panic!("unexpected warning {warning}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, ["Over", "Wraps", "Negative", "Fraction"]);
+    }
+}
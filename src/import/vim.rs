@@ -0,0 +1,199 @@
+use crate::{
+    import::highlights::{parse_blend, ImportWarning, ImportedHighlights},
+    information::Background,
+    sections::color_spec::{parser::Color, ColorFormat, ColorSpec, Style},
+    vimscript::STYLE_NAMES,
+};
+
+/// Reads the `hi` and `hi link` commands of a Vim colorscheme.
+///
+/// `set background=` and `let g:colors_name =` are picked up as well, everything else is
+/// ignored.
+pub fn import_vim(input: &str) -> ImportedHighlights {
+    let mut imported = ImportedHighlights::default();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim().trim_start_matches(':');
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.as_slice() {
+            [command, rest @ ..] if is_highlight_command(command) => {
+                let rest = match rest {
+                    ["default" | "def", rest @ ..] => rest,
+                    rest => rest,
+                };
+                match rest {
+                    [] | ["clear", ..] => (),
+                    ["link", from, to] => {
+                        imported
+                            .highlights
+                            .insert(from.to_string(), ColorSpec::Link(to.to_string()));
+                    }
+                    [hl_group, attributes @ ..] if !attributes.is_empty() => {
+                        let format = parse_attributes(hl_group, attributes, &mut imported.warnings);
+                        imported
+                            .highlights
+                            .insert(hl_group.to_string(), ColorSpec::Color(format));
+                    }
+                    _ => imported.warnings.push(ImportWarning::Unparsed {
+                        line: index + 1,
+                        text: line.to_string(),
+                    }),
+                }
+            }
+            ["set" | "se", option] => match *option {
+                "background=light" | "bg=light" => imported.background = Some(Background::Light),
+                "background=dark" | "bg=dark" => imported.background = Some(Background::Dark),
+                _ => (),
+            },
+            ["let", ..] if line.contains("colors_name") => {
+                if let Some((_, name)) = line.split_once('=') {
+                    let name = name.trim().trim_matches(|c| c == '"' || c == '\'');
+                    imported.name = Some(name.to_string());
+                }
+            }
+            ["exe" | "execute", ..] if line.contains("hi") => {
+                imported.warnings.push(ImportWarning::Unparsed {
+                    line: index + 1,
+                    text: line.to_string(),
+                })
+            }
+            _ => (),
+        }
+    }
+
+    imported
+}
+
+/// `hi`, `highlight` and anything in between, with an optional `!`.
+fn is_highlight_command(word: &str) -> bool {
+    let word = word.strip_suffix('!').unwrap_or(word);
+    word.len() >= 2 && "highlight".starts_with(word)
+}
+
+/// Parses the `key=value` attributes of a highlight group, only the GUI ones are kept.
+pub(crate) fn parse_attributes(
+    hl_group: &str,
+    attributes: &[&str],
+    warnings: &mut Vec<ImportWarning>,
+) -> ColorFormat {
+    let mut format = ColorFormat {
+        fg: None,
+        bg: None,
+        style: None,
+        special: None,
+        blend: None,
+    };
+
+    for attribute in attributes {
+        let Some((key, value)) = attribute.split_once('=') else {
+            continue;
+        };
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        match key {
+            "guifg" => format.fg = parse_color(hl_group, key, value, warnings),
+            "guibg" => format.bg = parse_color(hl_group, key, value, warnings),
+            "guisp" => format.special = parse_color(hl_group, key, value, warnings),
+            "gui" => {
+                format.style = Some(parse_style(value)).filter(|style| !style.is_empty());
+            }
+            "blend" => format.blend = parse_blend(hl_group, value.parse().ok(), value, warnings),
+            _ => (),
+        }
+    }
+
+    format
+}
+
+fn parse_color(
+    hl_group: &str,
+    attribute: &str,
+    value: &str,
+    warnings: &mut Vec<ImportWarning>,
+) -> Option<Color> {
+    if value.eq_ignore_ascii_case("none") {
+        return None;
+    }
+    match value.parse() {
+        Ok(color) => Some(Color::Color(color)),
+        Err(_) => {
+            warnings.push(ImportWarning::Unresolved {
+                hl_group: hl_group.to_string(),
+                attribute: attribute.to_string(),
+                expression: value.to_string(),
+            });
+            None
+        }
+    }
+}
+
+/// Parses a comma separated attribute list like `bold,italic`.
+pub(crate) fn parse_style(value: &str) -> Style {
+    value
+        .split(',')
+        .filter_map(|name| {
+            let name = if name == "inverse" { "reverse" } else { name };
+            STYLE_NAMES
+                .iter()
+                .find(|(style_name, _)| *style_name == name)
+                .map(|(_, style)| *style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sections::color_spec::parser::SpecString;
+
+    fn spec(imported: &ImportedHighlights, hl_group: &str) -> String {
+        SpecString(&imported.highlights[hl_group]).to_string()
+    }
+
+    #[test]
+    fn highlight_commands() {
+        let imported = import_vim(
+            r#"
+set background=dark
+let g:colors_name = "sample"
+hi clear
+highlight Normal guifg=#c0c0c0 guibg=#101010 ctermfg=250
+hi! Comment guifg=#808080 gui=italic,inverse guisp=NONE
+:hi link Title Normal
+hi def link Question Title
+hi! default link MoreMsg Question
+exe "hi Todo guifg=" . s:red
+"#,
+        );
+
+        assert_eq!(imported.name.as_deref(), Some("sample"));
+        assert!(matches!(imported.background, Some(Background::Dark)));
+        assert_eq!(spec(&imported, "Normal"), "#c0c0c0 #101010");
+        assert_eq!(spec(&imported, "Comment"), "#808080 - ir");
+        assert_eq!(spec(&imported, "Title"), "link:Normal");
+        assert_eq!(spec(&imported, "Question"), "link:Title");
+        assert_eq!(spec(&imported, "MoreMsg"), "link:Question");
+        assert!(matches!(
+            imported.warnings.as_slice(),
+            [ImportWarning::Unparsed { line: 10, .. }]
+        ));
+    }
+
+    #[test]
+    fn blend_is_a_percentage() {
+        let imported =
+            import_vim("hi Full blend=100\nhi Pmenu guifg=#101010 blend=150\nhi Word blend=half\n");
+
+        assert_eq!(spec(&imported, "Full"), "- - - - 100");
+        assert_eq!(spec(&imported, "Pmenu"), "#101010");
+        assert_eq!(spec(&imported, "Word"), "-");
+        let invalid = imported
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                ImportWarning::InvalidBlend { blend, .. } => blend.as_str(),
+                warning => panic!("unexpected warning {warning}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(invalid, ["150", "half"]);
+    }
+}
@@ -6,9 +6,27 @@ pub struct Information {
     pub name: String,
     pub background: Background,
     pub author: String,
+    /// Set `termguicolors` when the colorscheme is loaded.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub termguicolors: bool,
+    /// Emit the closest xterm-256 colors for terminals without truecolor support.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cterm: bool,
+    /// Link the modern `@` captures to legacy `TS*` groups when generating, see
+    /// [`link_modern_captures`](crate::migrate::link_modern_captures).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub modern_captures: bool,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+fn default_true() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum Background {
     Light,
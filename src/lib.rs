@@ -1,10 +1,20 @@
 use crate::{
+    check::CheckSection,
+    extras::{
+        base16::Base16Section, textmate::TextMateSection, tmux::TmuxSection, ExtrasError, Target,
+    },
     formatters::{InitLua, InitSetup, VimColorsFile},
     global::Global,
     information::Information,
+    lualine::{Lualine, LualineTheme},
     macros::write_file,
     palette::{InnerPalette, Palette},
-    sections::{Sections, SectionsFormatter, ThemeHighlights},
+    sections::{
+        semantic_tokens::SemanticTokens, MissingPaletteColor, Sections, SectionsFormatter,
+        ThemeHighlights,
+    },
+    terminal::{Terminal, TerminalColors},
+    vimscript::VimScript,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -12,26 +22,77 @@ use std::{
     fs::{self, File},
     io::{self, BufWriter, Write},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
+pub mod canonicalize;
+pub mod check;
 pub mod cli;
+pub mod extras;
 pub mod formatters;
 pub mod global;
+pub mod import;
 pub mod information;
+pub mod lualine;
 pub(crate) mod macros;
+pub mod migrate;
 pub mod palette;
+pub mod preview;
 pub mod sections;
+pub mod terminal;
+pub mod variant;
+pub mod vimscript;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Template {
     pub information: Information,
     pub palette: Palette,
     pub global: Option<Global>,
+    pub terminal: Option<Terminal>,
+    pub lualine: Option<Lualine>,
+    pub tmux: Option<TmuxSection>,
+    pub textmate: Option<TextMateSection>,
+    pub base16: Option<Base16Section>,
+    pub check: Option<CheckSection>,
+    /// Moved into `sections` as their `@lsp.*` groups by [`Template::from_str`].
+    pub semantic_tokens: Option<SemanticTokens>,
     #[serde(flatten)]
     pub sections: Sections,
 }
 
+impl FromStr for Template {
+    type Err = toml::de::Error;
+
+    /// Parses a template and expands its `[semantic_tokens]` into a section.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut template: Template = toml::from_str(input)?;
+        if let Some(semantic_tokens) = template.semantic_tokens.take() {
+            template.sections.0.insert(
+                "semantic_tokens".to_string(),
+                semantic_tokens.into_section(),
+            );
+        }
+        Ok(template)
+    }
+}
+
 impl Template {
+    /// Checks that every palette color the highlight groups, `[terminal]`, `[lualine]` and
+    /// `[tmux]` use exists.
+    pub fn check_colors(&self) -> Result<(), MissingPaletteColor> {
+        self.sections.check_colors(&self.palette)?;
+        if let Some(terminal) = &self.terminal {
+            terminal.check_colors(&self.palette)?;
+        }
+        if let Some(lualine) = &self.lualine {
+            lualine.check_colors(&self.palette)?;
+        }
+        if let Some(tmux) = &self.tmux {
+            tmux.check_colors(&self.palette)?;
+        }
+        Ok(())
+    }
+
     pub fn generate(&self, base_path: &Path) -> io::Result<()> {
         let name = Path::new(&self.information.name);
         self.setup_directories(base_path)?;
@@ -48,6 +109,36 @@ impl Template {
 
         write_file!([base_path, "lua", name, "theme.lua"], self.generate_theme(),)?;
 
+        if let Some(lualine) = self.generate_lualine() {
+            let lualine_path = name.with_extension("lua");
+            write_file!(
+                [base_path, "lua", "lualine", "themes", lualine_path],
+                lualine,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the themes for other programs into `<base_path>/extras`.
+    pub fn generate_extras(&self, targets: &[Target], base_path: &Path) -> Result<(), ExtrasError> {
+        for target in targets {
+            let path = base_path.join(target.path(&self.information.name));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, target.render(self)?)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `colors/<name>.vim` for Vim instead of the Lua module.
+    pub fn generate_vim(&self, base_path: &Path) -> io::Result<()> {
+        fs::create_dir_all([base_path, Path::new("colors")].iter().collect::<PathBuf>())?;
+
+        let name = Path::new(&self.information.name).with_extension("vim");
+        write_file!([base_path, "colors", name], self.generate_vim_script(),)?;
+
         Ok(())
     }
 
@@ -59,34 +150,79 @@ impl Template {
                 .collect::<PathBuf>(),
         )?;
         fs::create_dir_all([base_path, Path::new("colors")].iter().collect::<PathBuf>())?;
+        if self.lualine.is_some() {
+            fs::create_dir_all(
+                [
+                    base_path,
+                    Path::new("lua"),
+                    Path::new("lualine"),
+                    Path::new("themes"),
+                ]
+                .iter()
+                .collect::<PathBuf>(),
+            )?;
+        }
         Ok(())
     }
 
-    pub fn generate_init(&self) -> InitLua {
+    pub fn generate_init(&self) -> InitLua<'_> {
         InitLua {
             name: &self.information.name,
             background: self.information.background,
+            termguicolors: self.information.termguicolors,
         }
     }
 
-    pub fn generate_vim_colors_file(&self) -> VimColorsFile {
+    pub fn generate_vim_colors_file(&self) -> VimColorsFile<'_> {
         VimColorsFile {
             name: &self.information.name,
         }
     }
 
-    pub fn generate_theme(&self) -> SectionsFormatter {
+    pub fn generate_theme(&self) -> SectionsFormatter<'_> {
         SectionsFormatter {
             theme_name: &self.information.name,
             sections: &self.sections,
+            terminal: self.generate_terminal_colors("  "),
+            cterm: self.generate_cterm(),
+        }
+    }
+
+    pub fn generate_lualine(&self) -> Option<LualineTheme<'_>> {
+        self.lualine.as_ref().map(|lualine| LualineTheme {
+            theme_name: &self.information.name,
+            lualine,
+        })
+    }
+
+    pub fn generate_cterm(&self) -> Option<&Palette> {
+        self.information.cterm.then_some(&self.palette)
+    }
+
+    pub fn generate_terminal_colors<'a>(&'a self, indent: &'a str) -> TerminalColors<'a> {
+        TerminalColors {
+            terminal: self.terminal.as_ref(),
+            palette: &self.palette,
+            indent,
+        }
+    }
+
+    pub fn generate_vim_script(&self) -> VimScript<'_> {
+        VimScript {
+            information: &self.information,
+            palette: &self.palette,
+            global: self.global.as_ref(),
+            terminal: self.terminal.as_ref(),
+            sections: &self.sections,
         }
     }
 
-    pub fn generate_single_file(&self) -> SingleFile {
+    pub fn generate_single_file(&self) -> SingleFile<'_> {
         SingleFile {
             init_setup: InitSetup {
                 name: &self.information.name,
                 background: self.information.background,
+                termguicolors: self.information.termguicolors,
                 indent: "",
             },
             palette: InnerPalette {
@@ -98,7 +234,9 @@ impl Template {
                 theme_name: &self.information.name,
                 sections: &self.sections,
                 indent: "",
+                cterm: self.generate_cterm(),
             },
+            terminal: self.generate_terminal_colors(""),
         }
     }
 }
@@ -108,6 +246,7 @@ pub struct SingleFile<'a> {
     pub palette: InnerPalette<'a>,
     pub theme: ThemeHighlights<'a>,
     pub global: Option<&'a Global>,
+    pub terminal: TerminalColors<'a>,
 }
 
 impl SingleFile<'_> {
@@ -128,6 +267,6 @@ impl Display for SingleFile<'_> {
         write!(f, "{}\n\n", self.palette)?;
         write!(f, "local hl = vim.api.nvim_set_hl")?;
         write!(f, "{}", self.theme)?;
-        Ok(())
+        write!(f, "{}", self.terminal)
     }
 }
@@ -0,0 +1,121 @@
+use crate::{
+    palette::Palette,
+    sections::{
+        check_palette_ref,
+        color_spec::{parser::Color, Style},
+        MissingPaletteColor,
+    },
+    vimscript::VimStyle,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The `[lualine]` section, generating `lua/lualine/themes/<name>.lua`.
+///
+/// Modes that aren't set fall back to `normal` in lualine.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lualine {
+    pub normal: Option<LualineMode>,
+    pub insert: Option<LualineMode>,
+    pub visual: Option<LualineMode>,
+    pub replace: Option<LualineMode>,
+    pub command: Option<LualineMode>,
+    pub inactive: Option<LualineMode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LualineMode {
+    pub a: Option<LualineColors>,
+    pub b: Option<LualineColors>,
+    pub c: Option<LualineColors>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LualineColors {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub gui: Option<Style>,
+}
+
+impl Lualine {
+    /// The modes with their names in lualine.
+    fn modes(&self) -> [(&'static str, &Option<LualineMode>); 6] {
+        [
+            ("normal", &self.normal),
+            ("insert", &self.insert),
+            ("visual", &self.visual),
+            ("replace", &self.replace),
+            ("command", &self.command),
+            ("inactive", &self.inactive),
+        ]
+    }
+
+    /// Checks that every palette color of the modes exists.
+    pub fn check_colors(&self, palette: &Palette) -> Result<(), MissingPaletteColor> {
+        for (name, mode) in self.modes() {
+            let Some(LualineMode { a, b, c }) = mode else {
+                continue;
+            };
+            for (section, colors) in [("a", a), ("b", b), ("c", c)] {
+                if let Some(LualineColors { fg, bg, .. }) = colors {
+                    let key = format!("{name}.{section}");
+                    check_palette_ref(fg, palette, "lualine", &key)?;
+                    check_palette_ref(bg, palette, "lualine", &key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct LualineTheme<'a> {
+    pub theme_name: &'a str,
+    pub lualine: &'a Lualine,
+}
+
+impl Display for LualineTheme<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\
+local c = require('{theme_name}.palette')
+
+local theme = {{",
+            theme_name = self.theme_name
+        )?;
+
+        for (name, mode) in self.lualine.modes() {
+            let Some(LualineMode { a, b, c }) = mode else {
+                continue;
+            };
+            write!(f, "\n  {name} = {{")?;
+            for (section, colors) in [("a", a), ("b", b), ("c", c)] {
+                if let Some(colors) = colors {
+                    write!(f, "\n    {section} = {colors},")?;
+                }
+            }
+            write!(f, "\n  }},")?;
+        }
+
+        write!(f, "\n}}\n\nreturn theme")
+    }
+}
+
+impl Display for LualineColors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        if let Some(fg) = &self.fg {
+            write!(f, " fg = {fg},")?;
+        }
+        if let Some(bg) = &self.bg {
+            write!(f, " bg = {bg},")?;
+        }
+        if let Some(gui) = self.gui.filter(|gui| !gui.is_empty()) {
+            write!(f, " gui = '{}',", VimStyle(gui))?;
+        }
+        write!(f, " }}")
+    }
+}
@@ -5,8 +5,8 @@ use std::{
     path::{Path, PathBuf},
 };
 
-pub(crate) fn write_fmt<'a>(path: &Path, format: impl Display) -> io::Result<()> {
-    let mut file = BufWriter::new(File::create(path.into_iter().collect::<PathBuf>())?);
+pub(crate) fn write_fmt(path: &Path, format: impl Display) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path.iter().collect::<PathBuf>())?);
     file.write_fmt(format_args!("{}", format))?;
     Ok(())
 }
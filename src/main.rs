@@ -1,26 +1,83 @@
 use clap::Parser;
-use colorgen_nvim::{cli::ColorgenArgs, Template};
-use std::{error, fs::read_to_string, path::Path};
+use colorgen_nvim::{
+    canonicalize::{canonicalize, CanonicalizeOptions},
+    check::{contrast::ContrastReport, coverage::CoverageReport, cvd::DistinctReport, CheckError},
+    cli::{
+        CheckArgs, ColorgenArgs, Command, DeriveLightArgs, FmtArgs, FromImageArgs, GenerateArgs,
+        ImportArgs, ImportColorschemeArgs, ImportCommand, MigrateArgs, PaletteCommand, PreviewArgs,
+    },
+    import::{
+        base16::Base16Scheme,
+        dump::import_highlight_dump,
+        highlights::{template_to_string, ImportedHighlights},
+        lua::import_lua,
+        vim::import_vim,
+        ImportError,
+    },
+    migrate::{self, link_modern_captures},
+    palette::ExtractedPalette,
+    preview::{
+        ansi::AnsiPreview,
+        html::HtmlPreview,
+        svg::{render_png, SvgScreenshot},
+        Preview,
+    },
+    variant, Template,
+};
+use std::{
+    error,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-fn main() {
+fn main() -> ExitCode {
     match inner() {
-        Ok(_) => {}
-        Err(err) => println!("{err}"),
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            println!("{err}");
+            ExitCode::FAILURE
+        }
     }
 }
 
 fn inner() -> Result<(), Box<dyn error::Error>> {
     let args = ColorgenArgs::parse();
-    let template: Template = toml::from_str(&read_to_string(&args.filename)?)?;
 
-    match args.single_file {
+    match args.command {
+        None => generate(args.generate),
+        Some(Command::Fmt(args)) => fmt(args),
+        Some(Command::Preview(args)) => preview(args),
+        Some(Command::Migrate(args)) => migrate(args),
+        Some(Command::DeriveLight(args)) => derive_light(args),
+        Some(Command::Check(args)) => check(args),
+        Some(Command::Import(command)) => import(command),
+        Some(Command::Palette(PaletteCommand::FromImage(args))) => palette_from_image(args),
+    }
+}
+
+fn generate(args: GenerateArgs) -> Result<(), Box<dyn error::Error>> {
+    let filename = args.filename.expect("clap requires a filename");
+    let mut template: Template = read_to_string(filename)?.parse()?;
+    if template.information.modern_captures {
+        link_modern_captures(&mut template.sections);
+    }
+    template.check_colors()?;
+
+    let base_path = match args.single_file {
         false => {
-            template.generate(
-                &args
-                    .output
-                    .as_deref()
-                    .unwrap_or(Path::new(&template.information.name)),
-            )?;
+            let base_path = args
+                .output
+                .unwrap_or_else(|| PathBuf::from(&template.information.name));
+            if args.vim {
+                for warning in template.generate_vim_script().warnings() {
+                    eprintln!("warning: {warning}");
+                }
+                template.generate_vim(&base_path)?;
+            } else {
+                template.generate(&base_path)?;
+            }
+            base_path
         }
         true => {
             let path = match args.output {
@@ -35,8 +92,172 @@ fn inner() -> Result<(), Box<dyn error::Error>> {
             };
 
             template.generate_single_file().write_to_file(&path)?;
+            path.parent().map(Path::to_path_buf).unwrap_or_default()
+        }
+    };
+
+    template.generate_extras(&args.targets, &base_path)?;
+
+    Ok(())
+}
+
+fn fmt(args: FmtArgs) -> Result<(), Box<dyn error::Error>> {
+    let input = read_to_string(&args.filename)?;
+    let output = canonicalize(&input, CanonicalizeOptions { sort: args.sort })?;
+
+    if args.check {
+        if input != output {
+            return Err(format!("{} is not formatted", args.filename.display()).into());
+        }
+    } else if input != output {
+        write(&args.filename, output)?;
+    }
+
+    Ok(())
+}
+
+fn preview(args: PreviewArgs) -> Result<(), Box<dyn error::Error>> {
+    let mut template: Template = read_to_string(&args.filename)?.parse()?;
+    template.check_colors()?;
+    if template.information.modern_captures {
+        link_modern_captures(&mut template.sections);
+    }
+
+    let preview = Preview::new(&template);
+    if let Some(path) = &args.html {
+        write(path, HtmlPreview(&preview).to_string())?;
+    }
+    if args.svg.is_some() || args.png.is_some() {
+        let svg = SvgScreenshot(&preview).to_string();
+        if let Some(path) = &args.svg {
+            write(path, &svg)?;
+        }
+        if let Some(path) = &args.png {
+            write(path, render_png(&svg, args.scale)?)?;
         }
     }
+    if args.html.is_none() && args.svg.is_none() && args.png.is_none() {
+        print!("{}", AnsiPreview(&preview));
+    }
+    Ok(())
+}
+
+fn migrate(args: MigrateArgs) -> Result<(), Box<dyn error::Error>> {
+    let input = read_to_string(&args.filename)?;
+    let (output, report) = migrate::migrate(&input)?;
+
+    print!("{report}");
+    if !args.dry_run && !report.renamed.is_empty() {
+        write(&args.filename, output)?;
+    }
+
+    Ok(())
+}
+
+fn derive_light(args: DeriveLightArgs) -> Result<(), Box<dyn error::Error>> {
+    let template = variant::derive_light(&read_to_string(&args.filename)?, args.name.as_deref())?;
+
+    match args.output {
+        Some(output) => write(output, template)?,
+        None => print!("{template}"),
+    }
+
+    Ok(())
+}
+
+fn check(args: CheckArgs) -> Result<(), Box<dyn error::Error>> {
+    let template: Template = read_to_string(&args.filename)?.parse()?;
+    template.check_colors()?;
+
+    let mut thresholds = template.check.clone().unwrap_or_default();
+    thresholds.wcag = args.min_wcag.or(thresholds.wcag);
+    thresholds.apca = args.min_apca.or(thresholds.apca);
+    thresholds.delta_e = args.min_delta_e.or(thresholds.delta_e);
+
+    if args.coverage {
+        print!("{}", CoverageReport::new(&template));
+        // on its own `--coverage` is a report, the checks only run when asked for as well
+        let checks = args.cvd
+            || args.min_wcag.is_some()
+            || args.min_apca.is_some()
+            || args.min_delta_e.is_some();
+        if !checks {
+            return Ok(());
+        }
+        println!();
+    }
+    let mut contrast = ContrastReport::new(&template);
+    let contrast_failures = contrast.check(&thresholds);
+    print!("{contrast}");
+    let distinct = DistinctReport::new(&template);
+    if args.cvd {
+        print!("\n{distinct}");
+    }
+
+    let distinct_failures = distinct.failures(thresholds.delta_e);
+    if !distinct_failures.pairs.is_empty() && !args.cvd {
+        print!("\n{distinct_failures}");
+    }
+
+    if contrast_failures > 0 || !distinct_failures.pairs.is_empty() {
+        return Err(CheckError::Failed {
+            contrast: contrast_failures,
+            distinct: distinct_failures.pairs.len(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn import(command: ImportCommand) -> Result<(), Box<dyn error::Error>> {
+    let (args, template) = match command {
+        ImportCommand::Base16(args) => {
+            let scheme = Base16Scheme::from_yaml(&read_to_string(&args.filename)?)?;
+            let template = scheme.to_template()?;
+            (args, template)
+        }
+        ImportCommand::Lua(args) => import_colorscheme(args, |input| Ok(import_lua(input)))?,
+        ImportCommand::Vim(args) => import_colorscheme(args, |input| Ok(import_vim(input)))?,
+        ImportCommand::Highlights(args) => import_colorscheme(args, import_highlight_dump)?,
+    };
+
+    match args.output {
+        Some(output) => write(output, template)?,
+        None => print!("{template}"),
+    }
+
+    Ok(())
+}
+
+fn import_colorscheme(
+    args: ImportColorschemeArgs,
+    parse: fn(&str) -> Result<ImportedHighlights, ImportError>,
+) -> Result<(ImportArgs, String), Box<dyn error::Error>> {
+    let imported = parse(&read_to_string(&args.import.filename)?)?;
+    for warning in &imported.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let name = args
+        .import
+        .filename
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let template = imported.to_template(&name, args.merge_distance);
+    Ok((args.import, template_to_string(&template)?))
+}
+
+fn palette_from_image(args: FromImageArgs) -> Result<(), Box<dyn error::Error>> {
+    let extracted =
+        ExtractedPalette::from_image(&args.filename, args.count.into(), args.background)?;
+    let palette = extracted.to_toml()?;
+
+    match args.output {
+        Some(output) => write(output, palette)?,
+        None => print!("{palette}"),
+    }
 
     Ok(())
 }
@@ -0,0 +1,292 @@
+use crate::{
+    canonicalize::{canonicalize, highlight_tables, CanonicalizeError, CanonicalizeOptions},
+    sections::{color_spec::ColorSpec, Sections},
+};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+};
+use toml_edit::{Document, Key, Value};
+
+/// Legacy highlight groups and their Neovim 0.10 captures, `None` if the capture was removed.
+///
+/// Captures starting with `@` also match their sub-captures, e.g. `@text.title.1` becomes
+/// `@markup.heading.1`.
+const LEGACY_GROUPS: &[(&str, Option<&str>)] = &[
+    // nvim-treesitter's `TS*` groups
+    ("TSAnnotation", Some("@attribute")),
+    ("TSAttribute", Some("@attribute")),
+    ("TSBoolean", Some("@boolean")),
+    ("TSCharacter", Some("@character")),
+    ("TSCharacterSpecial", Some("@character.special")),
+    ("TSComment", Some("@comment")),
+    ("TSConditional", Some("@keyword.conditional")),
+    ("TSConstant", Some("@constant")),
+    ("TSConstBuiltin", Some("@constant.builtin")),
+    ("TSConstMacro", Some("@constant.macro")),
+    ("TSConstructor", Some("@constructor")),
+    ("TSDanger", Some("@comment.error")),
+    ("TSDebug", Some("@keyword.debug")),
+    ("TSDefine", Some("@keyword.directive.define")),
+    ("TSEmphasis", Some("@markup.italic")),
+    ("TSEnvironment", None),
+    ("TSEnvironmentName", None),
+    ("TSError", None),
+    ("TSException", Some("@keyword.exception")),
+    ("TSField", Some("@variable.member")),
+    ("TSFloat", Some("@number.float")),
+    ("TSFuncBuiltin", Some("@function.builtin")),
+    ("TSFuncMacro", Some("@function.macro")),
+    ("TSFunction", Some("@function")),
+    ("TSFunctionCall", Some("@function.call")),
+    ("TSInclude", Some("@keyword.import")),
+    ("TSKeyword", Some("@keyword")),
+    ("TSKeywordFunction", Some("@keyword.function")),
+    ("TSKeywordOperator", Some("@keyword.operator")),
+    ("TSKeywordReturn", Some("@keyword.return")),
+    ("TSLabel", Some("@label")),
+    ("TSLiteral", Some("@markup.raw")),
+    ("TSMath", Some("@markup.math")),
+    ("TSMethod", Some("@function.method")),
+    ("TSMethodCall", Some("@function.method.call")),
+    ("TSNamespace", Some("@module")),
+    ("TSNone", None),
+    ("TSNote", Some("@comment.note")),
+    ("TSNumber", Some("@number")),
+    ("TSOperator", Some("@operator")),
+    ("TSParameter", Some("@variable.parameter")),
+    ("TSParameterReference", None),
+    ("TSPreProc", Some("@keyword.directive")),
+    ("TSProperty", Some("@property")),
+    ("TSPunctBracket", Some("@punctuation.bracket")),
+    ("TSPunctDelimiter", Some("@punctuation.delimiter")),
+    ("TSPunctSpecial", Some("@punctuation.special")),
+    ("TSRepeat", Some("@keyword.repeat")),
+    ("TSStorageClass", Some("@keyword.modifier")),
+    ("TSStrike", Some("@markup.strikethrough")),
+    ("TSString", Some("@string")),
+    ("TSStringEscape", Some("@string.escape")),
+    ("TSStringRegex", Some("@string.regexp")),
+    ("TSStringSpecial", Some("@string.special")),
+    ("TSStrong", Some("@markup.strong")),
+    ("TSStructure", None),
+    ("TSSymbol", Some("@string.special.symbol")),
+    ("TSTag", Some("@tag")),
+    ("TSTagAttribute", Some("@tag.attribute")),
+    ("TSTagDelimiter", Some("@tag.delimiter")),
+    ("TSText", None),
+    ("TSTextReference", Some("@markup.link")),
+    ("TSTitle", Some("@markup.heading")),
+    ("TSTodo", Some("@comment.todo")),
+    ("TSType", Some("@type")),
+    ("TSTypeBuiltin", Some("@type.builtin")),
+    ("TSTypeDefinition", Some("@type.definition")),
+    ("TSTypeQualifier", Some("@keyword.modifier")),
+    ("TSUnderline", Some("@markup.underline")),
+    ("TSURI", Some("@markup.link.url")),
+    ("TSVariable", Some("@variable")),
+    ("TSVariableBuiltin", Some("@variable.builtin")),
+    ("TSWarning", Some("@comment.warning")),
+    // captures renamed in nvim-treesitter 0.9.2
+    ("@annotation", Some("@attribute")),
+    ("@conditional", Some("@keyword.conditional")),
+    ("@debug", Some("@keyword.debug")),
+    ("@define", Some("@keyword.directive.define")),
+    ("@error", None),
+    ("@exception", Some("@keyword.exception")),
+    ("@field", Some("@variable.member")),
+    ("@float", Some("@number.float")),
+    ("@include", Some("@keyword.import")),
+    ("@method", Some("@function.method")),
+    ("@namespace", Some("@module")),
+    ("@none", None),
+    ("@parameter", Some("@variable.parameter")),
+    ("@parameter.reference", None),
+    ("@preproc", Some("@keyword.directive")),
+    ("@repeat", Some("@keyword.repeat")),
+    ("@storageclass", Some("@keyword.modifier")),
+    ("@string.regex", Some("@string.regexp")),
+    ("@symbol", Some("@string.special.symbol")),
+    ("@type.qualifier", Some("@keyword.modifier")),
+    // `@text.*` became `@markup.*`, except for the comment notes
+    ("@text", None),
+    ("@text.danger", Some("@comment.error")),
+    ("@text.diff.add", Some("@diff.plus")),
+    ("@text.diff.delete", Some("@diff.minus")),
+    ("@text.emphasis", Some("@markup.italic")),
+    ("@text.environment", None),
+    ("@text.literal", Some("@markup.raw")),
+    ("@text.math", Some("@markup.math")),
+    ("@text.note", Some("@comment.note")),
+    ("@text.quote", Some("@markup.quote")),
+    ("@text.reference", Some("@markup.link")),
+    ("@text.strike", Some("@markup.strikethrough")),
+    ("@text.strong", Some("@markup.strong")),
+    ("@text.title", Some("@markup.heading")),
+    ("@text.todo", Some("@comment.todo")),
+    ("@text.underline", Some("@markup.underline")),
+    ("@text.uri", Some("@markup.link.url")),
+    ("@text.warning", Some("@comment.warning")),
+];
+
+/// What became of a legacy highlight group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Legacy {
+    Renamed(String),
+    /// Neovim has no equivalent capture anymore.
+    Removed,
+}
+
+/// Looks up a legacy highlight group, `None` if the name is current.
+pub fn legacy_group(hl_group: &str) -> Option<Legacy> {
+    let mut prefix = hl_group;
+    loop {
+        if let Some((_, modern)) = LEGACY_GROUPS.iter().find(|(legacy, _)| *legacy == prefix) {
+            return Some(match modern {
+                Some(modern) => Legacy::Renamed(format!("{modern}{}", &hl_group[prefix.len()..])),
+                None => Legacy::Removed,
+            });
+        }
+        if !prefix.starts_with('@') {
+            return None;
+        }
+        prefix = prefix.rsplit_once('.')?.0;
+    }
+}
+
+/// The changes made by [`migrate`].
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// Legacy groups and their new names.
+    pub renamed: Vec<(String, String)>,
+    /// Legacy groups without a modern equivalent, they are left in place.
+    pub removed: Vec<String>,
+    /// Legacy groups whose modern name is already defined, they are left in place.
+    pub conflicts: Vec<(String, String)>,
+}
+
+impl MigrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.renamed.is_empty() && self.removed.is_empty() && self.conflicts.is_empty()
+    }
+}
+
+impl Display for MigrationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (legacy, modern) in &self.renamed {
+            writeln!(f, "renamed   {legacy} -> {modern}")?;
+        }
+        for (legacy, modern) in &self.conflicts {
+            writeln!(f, "kept      {legacy}, {modern} is already defined")?;
+        }
+        for legacy in &self.removed {
+            writeln!(f, "kept      {legacy}, it has no modern equivalent")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renames the legacy Treesitter groups of a template to their `@` captures.
+///
+/// Links to renamed groups are updated, comments and the order of the highlight groups are kept
+/// and the result is written in the canonical style (see [`canonicalize`]).
+pub fn migrate(input: &str) -> Result<(String, MigrationReport), CanonicalizeError> {
+    let mut document: Document = input.parse()?;
+    let mut report = MigrationReport::default();
+
+    let mut defined = highlight_tables(&mut document)
+        .into_iter()
+        .flat_map(|table| {
+            table
+                .iter()
+                .map(|(key, _)| key.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect::<HashSet<_>>();
+    let mut kept = HashSet::new();
+
+    for table in highlight_tables(&mut document) {
+        let keys = table
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .collect::<Vec<_>>();
+        for key in keys {
+            let (key, item) = table.remove_entry(&key).expect("the key was just listed");
+            let key = match legacy_group(key.get()) {
+                Some(Legacy::Renamed(modern)) if defined.insert(modern.clone()) => {
+                    report.renamed.push((key.get().to_string(), modern.clone()));
+                    Key::new(modern).with_decor(key.decor().clone())
+                }
+                Some(Legacy::Renamed(modern)) => {
+                    report.conflicts.push((key.get().to_string(), modern));
+                    kept.insert(key.get().to_string());
+                    key
+                }
+                Some(Legacy::Removed) => {
+                    report.removed.push(key.get().to_string());
+                    key
+                }
+                None => key,
+            };
+            table.insert_formatted(&key, item);
+        }
+    }
+
+    for table in highlight_tables(&mut document) {
+        for (_, item) in table.iter_mut() {
+            if let Some(value) = item.as_value_mut() {
+                rename_link(value, &kept);
+            }
+        }
+    }
+
+    let output = canonicalize(&document.to_string(), CanonicalizeOptions::default())?;
+    Ok((output, report))
+}
+
+/// Points `link:` specs to the modern name of a legacy group, unless the group was `kept`.
+fn rename_link(value: &mut Value, kept: &HashSet<String>) {
+    let modern = |link: &str| match legacy_group(link) {
+        Some(Legacy::Renamed(modern)) if !kept.contains(link) => Some(modern),
+        _ => None,
+    };
+
+    match value {
+        Value::String(string) => {
+            if let Some(modern) = string.value().strip_prefix("link:").and_then(modern) {
+                *value = format!("link:{modern}").into();
+            }
+        }
+        Value::InlineTable(table) => {
+            if let Some(modern) = table.get("link").and_then(Value::as_str).and_then(modern) {
+                table.insert("link", modern.into());
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Links the modern captures of legacy groups to them, for templates that haven't been migrated.
+///
+/// Captures that are already defined are left alone. The links are appended to the section of
+/// the legacy group.
+pub fn link_modern_captures(sections: &mut Sections) {
+    let mut links = Vec::new();
+    for (section_name, section) in &sections.0 {
+        for hl_group in section.0.keys() {
+            if let Some(Legacy::Renamed(modern)) = legacy_group(hl_group) {
+                if sections.get(&modern).is_none()
+                    && !links.iter().any(|(_, capture, _)| *capture == modern)
+                {
+                    links.push((section_name.clone(), modern, hl_group.clone()));
+                }
+            }
+        }
+    }
+
+    for (section_name, capture, hl_group) in links {
+        if let Some(section) = sections.0.get_mut(&section_name) {
+            section.0.insert(capture, ColorSpec::Link(hl_group));
+        }
+    }
+}
@@ -2,9 +2,17 @@ use linked_hash_map::LinkedHashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
+pub use cielab::Lab;
+pub use extract::{ExtractError, ExtractedPalette};
+pub use oklab::{linear_to_srgb, srgb_to_linear, Oklab, Oklch};
 pub use rgb_color::{RgbColor, RgbParsingError};
+pub use xterm::xterm_color;
 
+mod cielab;
+mod extract;
+mod oklab;
 mod rgb_color;
+mod xterm;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Palette(pub LinkedHashMap<String, RgbColor>);
@@ -0,0 +1,349 @@
+use crate::{
+    canonicalize::{canonicalize, CanonicalizeError, CanonicalizeOptions},
+    check::contrast::wcag_contrast,
+    import::highlights::color_name,
+    information::Background,
+    palette::{Oklab, Palette, RgbColor},
+};
+use linked_hash_map::LinkedHashMap;
+use std::{collections::HashMap, fmt::Write, path::Path};
+
+/// Images are scaled down to fit this size before their colors are counted.
+const THUMBNAIL_SIZE: u32 = 256;
+const KMEANS_ITERATIONS: usize = 20;
+
+/// The minimum WCAG contrast of `fg`, the comment color `gray` and the accents against `bg`.
+const FG_CONTRAST: f64 = 7.0;
+const GRAY_CONTRAST: f64 = 3.0;
+const ACCENT_CONTRAST: f64 = 4.5;
+
+/// Accents less colorful than this are made more colorful, keeping their hue.
+const MIN_ACCENT_CHROMA: f64 = 0.06;
+/// Accents closer than this (in Oklab) to an accent of the palette are left out.
+const MIN_ACCENT_DISTANCE: f64 = 0.05;
+
+/// `bg`, `alt_bg`, `fg` and `gray`, every other palette color is an accent.
+pub const ROLES: usize = 4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtractError {
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Canonicalize(#[from] CanonicalizeError),
+}
+
+/// A cluster of similar colors of an image.
+#[derive(Debug, Clone, Copy)]
+struct Cluster {
+    center: Oklab,
+    /// The number of pixels in the cluster.
+    weight: f64,
+}
+
+/// The palette of an image with the colors named by their role.
+pub struct ExtractedPalette {
+    pub background: Background,
+    pub palette: Palette,
+}
+
+impl ExtractedPalette {
+    /// Extracts up to `count` colors from a PNG or JPEG image, the background is derived from
+    /// the image unless given.
+    ///
+    /// Images with few distinct colors give fewer accents.
+    pub fn from_image(
+        path: &Path,
+        count: usize,
+        background: Option<Background>,
+    ) -> Result<Self, ExtractError> {
+        let image = image::open(path)?
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+            .to_rgb8();
+        // more clusters than colors leave a choice of accents
+        let clusters = kmeans(
+            &histogram(image.pixels().map(|pixel| pixel.0)),
+            2 * count.max(ROLES),
+        );
+        Ok(Self::from_clusters(clusters, count, background))
+    }
+
+    /// Assigns the roles: `bg` is the most used dark (or light) color, `fg` the lightest (or
+    /// darkest) one and the accents are the most colorful of the rest, each adjusted to be
+    /// readable on `bg`.
+    fn from_clusters(
+        mut clusters: Vec<Cluster>,
+        count: usize,
+        background: Option<Background>,
+    ) -> Self {
+        let total = clusters.iter().map(|cluster| cluster.weight).sum::<f64>();
+        let background = background.unwrap_or_else(|| {
+            let lightness = clusters
+                .iter()
+                .map(|cluster| cluster.center.l * cluster.weight)
+                .sum::<f64>()
+                / total;
+            if lightness > 0.5 {
+                Background::Light
+            } else {
+                Background::Dark
+            }
+        });
+        // how far a lightness is on the side of the background
+        let depth = |l: f64| match background {
+            Background::Dark => 1.0 - l,
+            Background::Light => l,
+        };
+
+        let bg = take_max(&mut clusters, |cluster| {
+            cluster.weight / total * depth(cluster.center.l).powi(2)
+        });
+        let fg = take_max(&mut clusters, |cluster| 1.0 - depth(cluster.center.l));
+
+        let (bg_lightness, alt_bg_offset, bg_chroma) = match background {
+            Background::Dark => (bg.center.l.clamp(0.16, 0.26), 0.05, 0.04),
+            Background::Light => (bg.center.l.clamp(0.92, 0.98), -0.05, 0.03),
+        };
+        let bg_color = with_lightness(limit_chroma(bg.center, bg_chroma), bg_lightness);
+        let alt_bg = with_lightness(bg_color, bg_lightness + alt_bg_offset);
+        let bg = RgbColor::from(bg_color);
+
+        let fg_color = limit_chroma(fg.center, 0.03);
+        let fg = with_contrast(fg_color, bg, FG_CONTRAST);
+        let gray = with_contrast(with_lightness(fg_color, bg_lightness), bg, GRAY_CONTRAST);
+
+        clusters.sort_by(|a, b| {
+            let score = |cluster: &Cluster| cluster.center.chroma() * cluster.weight.sqrt();
+            score(b).total_cmp(&score(a))
+        });
+        let mut accents = Vec::<(Oklab, RgbColor)>::new();
+        for cluster in clusters {
+            if accents.len() + ROLES >= count {
+                break;
+            }
+            let color = with_contrast(
+                min_chroma(cluster.center, MIN_ACCENT_CHROMA),
+                bg,
+                ACCENT_CONTRAST,
+            );
+            let oklab = Oklab::from(color);
+            if accents
+                .iter()
+                .all(|(accent, _)| accent.distance(&oklab) >= MIN_ACCENT_DISTANCE)
+            {
+                accents.push((oklab, color));
+            }
+        }
+        accents.sort_by(|(a, _), (b, _)| hue(a).total_cmp(&hue(b)));
+
+        let mut palette = LinkedHashMap::new();
+        palette.insert("bg".to_string(), bg);
+        palette.insert("alt_bg".to_string(), RgbColor::from(alt_bg));
+        palette.insert("fg".to_string(), fg);
+        palette.insert("gray".to_string(), gray);
+        for (_, color) in accents {
+            let base = color_name(color);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while palette.contains_key(&name) {
+                name = format!("{base}_{suffix}");
+                suffix += 1;
+            }
+            palette.insert(name, color);
+        }
+
+        ExtractedPalette {
+            background,
+            palette: Palette(palette),
+        }
+    }
+
+    /// A `[palette]` block to paste into a template.
+    pub fn to_toml(&self) -> Result<String, ExtractError> {
+        let mut output = format!("# background = \"{}\"\n[palette]\n", self.background);
+        for (name, color) in &self.palette.0 {
+            writeln!(output, "{name} = \"{color}\"").expect("writing to a String can't fail");
+        }
+        Ok(canonicalize(&output, CanonicalizeOptions::default())?)
+    }
+}
+
+/// Counts the colors of the pixels, similar colors are put in the same bin to keep the number
+/// of colors small.
+fn histogram(pixels: impl Iterator<Item = [u8; 3]>) -> Vec<Cluster> {
+    let mut bins = HashMap::<[u8; 3], ([f64; 3], f64)>::new();
+    for rgb in pixels {
+        let Oklab { l, a, b } = Oklab::from(RgbColor(rgb));
+        let (sum, weight) = bins.entry(rgb.map(|channel| channel >> 3)).or_default();
+        sum[0] += l;
+        sum[1] += a;
+        sum[2] += b;
+        *weight += 1.0;
+    }
+
+    let mut bins = bins.into_iter().collect::<Vec<_>>();
+    // `HashMap` iterates in a random order, sort to make the clustering deterministic
+    bins.sort_by_key(|(bin, _)| *bin);
+    bins.into_iter()
+        .map(|(_, ([l, a, b], weight))| Cluster {
+            center: Oklab {
+                l: l / weight,
+                a: a / weight,
+                b: b / weight,
+            },
+            weight,
+        })
+        .collect()
+}
+
+/// Weighted k-means in Oklab.
+///
+/// The first center is the most used color, every following one the color with the largest
+/// weighted squared distance to the centers so far.
+fn kmeans(colors: &[Cluster], k: usize) -> Vec<Cluster> {
+    let mut centers = Vec::<Oklab>::new();
+    while centers.len() < k.min(colors.len()) {
+        let next = colors.iter().max_by(|a, b| {
+            let score = |color: &Cluster| {
+                centers
+                    .iter()
+                    .map(|center| center.distance(&color.center).powi(2))
+                    .fold(1.0, f64::min)
+                    * color.weight
+            };
+            score(a).total_cmp(&score(b))
+        });
+        match next {
+            Some(next) if !centers.contains(&next.center) => centers.push(next.center),
+            _ => break,
+        }
+    }
+
+    let mut clusters = Vec::new();
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![([0.0; 3], 0.0); centers.len()];
+        for color in colors {
+            let nearest = (0..centers.len())
+                .min_by(|&a, &b| {
+                    centers[a]
+                        .distance(&color.center)
+                        .total_cmp(&centers[b].distance(&color.center))
+                })
+                .expect("there is at least one center");
+            let (sum, weight) = &mut sums[nearest];
+            sum[0] += color.center.l * color.weight;
+            sum[1] += color.center.a * color.weight;
+            sum[2] += color.center.b * color.weight;
+            *weight += color.weight;
+        }
+
+        clusters = sums
+            .into_iter()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|([l, a, b], weight)| Cluster {
+                center: Oklab {
+                    l: l / weight,
+                    a: a / weight,
+                    b: b / weight,
+                },
+                weight,
+            })
+            .collect::<Vec<_>>();
+        let moved = clusters
+            .iter()
+            .zip(&centers)
+            .any(|(cluster, center)| cluster.center.distance(center) > 1e-4);
+        centers = clusters.iter().map(|cluster| cluster.center).collect();
+        if !moved {
+            break;
+        }
+    }
+    clusters
+}
+
+/// Removes and returns the cluster with the highest score.
+fn take_max(clusters: &mut Vec<Cluster>, score: impl Fn(&Cluster) -> f64) -> Cluster {
+    let index = (0..clusters.len())
+        .max_by(|&a, &b| score(&clusters[a]).total_cmp(&score(&clusters[b])))
+        .expect("an image has at least one color");
+    // keep the last cluster for images with a single color
+    if clusters.len() == 1 {
+        clusters[0]
+    } else {
+        clusters.remove(index)
+    }
+}
+
+fn hue(color: &Oklab) -> f64 {
+    color.b.atan2(color.a).to_degrees().rem_euclid(360.0)
+}
+
+fn with_lightness(color: Oklab, l: f64) -> Oklab {
+    Oklab { l, ..color }
+}
+
+fn scale_chroma(color: Oklab, chroma: f64) -> Oklab {
+    let scale = chroma / color.chroma();
+    Oklab {
+        a: color.a * scale,
+        b: color.b * scale,
+        ..color
+    }
+}
+
+fn limit_chroma(color: Oklab, max: f64) -> Oklab {
+    if color.chroma() > max {
+        scale_chroma(color, max)
+    } else {
+        color
+    }
+}
+
+fn min_chroma(color: Oklab, min: f64) -> Oklab {
+    // grays have no hue to keep
+    if color.chroma() < min && color.chroma() > 1e-3 {
+        scale_chroma(color, min)
+    } else {
+        color
+    }
+}
+
+/// Moves the lightness of a color away from the background until it has the contrast.
+fn with_contrast(color: Oklab, bg: RgbColor, min: f64) -> RgbColor {
+    let step = if Oklab::from(bg).l > 0.5 { -0.01 } else { 0.01 };
+    let mut color = color;
+    loop {
+        let rgb = RgbColor::from(color);
+        if wcag_contrast(rgb, bg) >= min || !(0.0..=1.0).contains(&(color.l + step)) {
+            return rgb;
+        }
+        color.l += step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dark image with a few light pixels, like a screenshot of text.
+    #[test]
+    fn two_color_image() {
+        let (dark, light) = ([0x10, 0x14, 0x20], [0xc0, 0xc0, 0xb0]);
+        let image = image::RgbImage::from_fn(64, 64, |x, _| {
+            image::Rgb(if x % 8 == 0 { light } else { dark })
+        });
+        let path =
+            std::env::temp_dir().join(format!("colorgen-two-colors-{}.png", std::process::id()));
+        image.save(&path).unwrap();
+        let extracted = ExtractedPalette::from_image(&path, 8, None);
+        std::fs::remove_file(&path).unwrap();
+        let extracted = extracted.unwrap();
+
+        assert!(matches!(extracted.background, Background::Dark));
+        let colors = &extracted.palette.0;
+        let (bg, fg) = (colors["bg"], colors["fg"]);
+        assert!(Oklab::from(bg).l < Oklab::from(fg).l);
+        assert!(wcag_contrast(fg, bg) >= FG_CONTRAST, "{fg} on {bg}");
+        assert!(wcag_contrast(colors["gray"], bg) >= GRAY_CONTRAST);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::palette::RgbColor;
+
+/// A color in the [Oklab](https://bottosson.github.io/posts/oklab/) color space.
+///
+/// Euclidean distances in Oklab roughly match perceived color differences.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Oklab {
+    pub fn distance(&self, other: &Oklab) -> f64 {
+        ((self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+
+    /// The colorfulness, the distance from the gray axis.
+    pub fn chroma(&self) -> f64 {
+        self.a.hypot(self.b)
+    }
+
+    fn to_linear_srgb(self) -> [f64; 3] {
+        let Oklab { l, a, b } = self;
+        let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+        [
+            4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_,
+            -1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_,
+            -0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_,
+        ]
+    }
+
+    fn in_srgb_gamut(self) -> bool {
+        self.to_linear_srgb()
+            .iter()
+            .all(|channel| (-1e-4..=1.0 + 1e-4).contains(channel))
+    }
+}
+
+impl From<RgbColor> for Oklab {
+    fn from(RgbColor(rgb): RgbColor) -> Self {
+        let [r, g, b] = rgb.map(|channel| srgb_to_linear(channel as f64 / 255.0));
+
+        let l = 0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b;
+        let m = 0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b;
+        let s = 0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b;
+
+        let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+        Oklab {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+}
+
+impl From<Oklab> for RgbColor {
+    /// Colors outside of the sRGB gamut are clamped per channel.
+    fn from(color: Oklab) -> Self {
+        RgbColor(
+            color
+                .to_linear_srgb()
+                .map(|channel| (linear_to_srgb(channel) * 255.0).round() as u8),
+        )
+    }
+}
+
+/// Oklab in polar coordinates: lightness, chroma and hue in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f64,
+    pub c: f64,
+    pub h: f64,
+}
+
+impl Oklch {
+    /// Converts to sRGB, reducing the chroma until the color is in the gamut so that the hue and
+    /// lightness are kept.
+    pub fn to_rgb_in_gamut(self) -> RgbColor {
+        let color = Oklab::from(self);
+        if color.in_srgb_gamut() {
+            return RgbColor::from(color);
+        }
+
+        let (mut low, mut high) = (0.0, self.c);
+        for _ in 0..20 {
+            let c = (low + high) / 2.0;
+            if Oklab::from(Oklch { c, ..self }).in_srgb_gamut() {
+                low = c;
+            } else {
+                high = c;
+            }
+        }
+        RgbColor::from(Oklab::from(Oklch { c: low, ..self }))
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(Oklab { l, a, b }: Oklab) -> Self {
+        Oklch {
+            l,
+            c: a.hypot(b),
+            h: b.atan2(a).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(Oklch { l, c, h }: Oklch) -> Self {
+        let (sin, cos) = h.to_radians().sin_cos();
+        Oklab {
+            l,
+            a: c * cos,
+            b: c * sin,
+        }
+    }
+}
+
+/// Converts an sRGB channel in `0.0..=1.0` to linear light.
+pub fn srgb_to_linear(channel: f64) -> f64 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear light channel back to sRGB, clamped to `0.0..=1.0`.
+pub fn linear_to_srgb(channel: f64) -> f64 {
+    let channel = channel.clamp(0.0, 1.0);
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
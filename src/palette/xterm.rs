@@ -0,0 +1,60 @@
+use crate::palette::{Oklab, RgbColor};
+
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// The color of an xterm-256 index.
+///
+/// The first 16 colors depend on the terminal's configuration, the standard xterm values are
+/// returned for them.
+pub fn xterm_color(index: u8) -> RgbColor {
+    const SYSTEM: [[u8; 3]; 16] = [
+        [0x00, 0x00, 0x00],
+        [0xcd, 0x00, 0x00],
+        [0x00, 0xcd, 0x00],
+        [0xcd, 0xcd, 0x00],
+        [0x00, 0x00, 0xee],
+        [0xcd, 0x00, 0xcd],
+        [0x00, 0xcd, 0xcd],
+        [0xe5, 0xe5, 0xe5],
+        [0x7f, 0x7f, 0x7f],
+        [0xff, 0x00, 0x00],
+        [0x00, 0xff, 0x00],
+        [0xff, 0xff, 0x00],
+        [0x5c, 0x5c, 0xff],
+        [0xff, 0x00, 0xff],
+        [0x00, 0xff, 0xff],
+        [0xff, 0xff, 0xff],
+    ];
+
+    match index {
+        0..=15 => RgbColor(SYSTEM[index as usize]),
+        16..=231 => {
+            let index = index - 16;
+            RgbColor([
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            ])
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            RgbColor([level; 3])
+        }
+    }
+}
+
+impl RgbColor {
+    /// The perceptually closest color of the xterm-256 color cube and grayscale ramp.
+    ///
+    /// The 16 system colors are skipped since terminals commonly redefine them.
+    pub fn to_xterm256(self) -> u8 {
+        let color = Oklab::from(self);
+        (16..=255)
+            .min_by(|a, b| {
+                let a = color.distance(&xterm_color(*a).into());
+                let b = color.distance(&xterm_color(*b).into());
+                a.total_cmp(&b)
+            })
+            .expect("the range isn't empty")
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    palette::{Palette, RgbColor},
+    sections::color_spec::{parser::Color, ColorSpec, Style},
+    Template,
+};
+
+pub mod ansi;
+pub mod html;
+pub mod svg;
+
+/// The code samples, written as `«<highlight group> <text>»` spans, and whether they get line
+/// numbers. A line starting with `«<highlight group>»` is highlighted as a whole, like
+/// `CursorLine`.
+pub(crate) const SAMPLES: &[(&str, bool, &str)] = &[
+    ("Editor", false, include_str!("preview/samples/editor.txt")),
+    ("Diff", false, include_str!("preview/samples/diff.txt")),
+    ("Rust", true, include_str!("preview/samples/rust.txt")),
+    ("Lua", true, include_str!("preview/samples/lua.txt")),
+    ("Python", true, include_str!("preview/samples/python.txt")),
+    (
+        "Markdown",
+        true,
+        include_str!("preview/samples/markdown.txt"),
+    ),
+];
+
+/// The groups Neovim links Treesitter captures to by default, for templates that only color the
+/// Vim syntax groups.
+const DEFAULT_LINKS: &[(&str, &str)] = &[
+    ("@attribute", "Macro"),
+    ("@boolean", "Boolean"),
+    ("@character", "Character"),
+    ("@character.special", "SpecialChar"),
+    ("@comment", "Comment"),
+    ("@constant", "Constant"),
+    ("@constructor", "Special"),
+    ("@diff.delta", "Changed"),
+    ("@diff.minus", "Removed"),
+    ("@diff.plus", "Added"),
+    ("@function", "Function"),
+    ("@keyword", "Keyword"),
+    ("@keyword.conditional", "Conditional"),
+    ("@keyword.directive", "PreProc"),
+    ("@keyword.exception", "Exception"),
+    ("@keyword.import", "Include"),
+    ("@keyword.repeat", "Repeat"),
+    ("@label", "Label"),
+    ("@markup.heading", "Title"),
+    ("@markup.link", "Underlined"),
+    ("@module", "Structure"),
+    ("@number", "Number"),
+    ("@operator", "Operator"),
+    ("@property", "Identifier"),
+    ("@punctuation", "Delimiter"),
+    ("@string", "String"),
+    ("@string.special", "SpecialChar"),
+    ("@tag", "Tag"),
+    ("@type", "Type"),
+];
+
+/// How a highlight group looks in Neovim.
+#[derive(Debug, Clone)]
+pub struct Swatch<'a> {
+    pub hl_group: &'a str,
+    /// The group this one links to.
+    pub link: Option<&'a str>,
+    /// `None` if the group doesn't set it, Neovim then uses the color of the text below.
+    pub fg: Option<RgbColor>,
+    pub bg: Option<RgbColor>,
+    pub special: Option<RgbColor>,
+    /// The style without `reverse`, which is applied by swapping `fg` and `bg`.
+    pub style: Style,
+}
+
+/// The palette and the highlight groups of a template, resolved for rendering.
+pub struct Preview<'a> {
+    pub template: &'a Template,
+    pub name: &'a str,
+    pub palette: &'a Palette,
+    /// `Normal`'s colors, for groups that don't set their own.
+    pub normal: Swatch<'a>,
+    pub sections: Vec<(&'a str, Vec<Swatch<'a>>)>,
+}
+
+impl<'a> Preview<'a> {
+    /// Resolves every highlight group, following links.
+    pub fn new(template: &'a Template) -> Self {
+        let normal = Swatch::new(template, "Normal", None);
+        let sections = template
+            .sections
+            .0
+            .iter()
+            .map(|(section_name, section)| {
+                let swatches = section
+                    .0
+                    .keys()
+                    .map(|hl_group| Swatch::new(template, hl_group, Some(&normal)))
+                    .collect();
+                (section_name.as_str(), swatches)
+            })
+            .collect();
+
+        Preview {
+            template,
+            name: &template.information.name,
+            palette: &template.palette,
+            normal,
+            sections,
+        }
+    }
+}
+
+impl<'a> Preview<'a> {
+    /// Resolves any highlight group like Neovim does, including the fallback of a Treesitter
+    /// capture to its parent captures (`@keyword.return` to `@keyword`) and to the Vim syntax
+    /// groups.
+    pub fn resolve<'b>(&'b self, hl_group: &'b str) -> Swatch<'b> {
+        let captures = || {
+            std::iter::successors(Some(hl_group), |capture| {
+                capture
+                    .starts_with('@')
+                    .then(|| capture.rsplit_once('.').map(|(parent, _)| parent))
+                    .flatten()
+            })
+        };
+        let sections = &self.template.sections;
+        let defined = captures()
+            .find(|capture| sections.get(capture).is_some())
+            .or_else(|| {
+                captures().find_map(|capture| {
+                    DEFAULT_LINKS
+                        .iter()
+                        .find(|(default, _)| *default == capture)
+                        .map(|(_, link)| *link)
+                })
+            })
+            .unwrap_or(hl_group);
+
+        Swatch {
+            hl_group,
+            ..Swatch::new(self.template, defined, Some(&self.normal))
+        }
+    }
+}
+
+impl<'a> Swatch<'a> {
+    /// Fills the colors that aren't set from the swatch below, like the text under a highlight.
+    pub fn over(&self, below: &Swatch) -> Swatch<'a> {
+        Swatch {
+            fg: self.fg.or(below.fg),
+            bg: self.bg.or(below.bg),
+            ..self.clone()
+        }
+    }
+
+    fn new(template: &'a Template, hl_group: &'a str, normal: Option<&Swatch>) -> Self {
+        let palette = &template.palette;
+        let resolve = |color: &Option<Color>| color.as_ref().and_then(|c| c.resolve(palette));
+        let link = match template.sections.get(hl_group) {
+            Some(ColorSpec::Link(link)) => Some(link.as_str()),
+            _ => None,
+        };
+        let format = template.sections.resolve(hl_group);
+
+        let mut fg = format.and_then(|format| resolve(&format.fg));
+        let mut bg = format.and_then(|format| resolve(&format.bg));
+        let mut style = format
+            .and_then(|format| format.style)
+            .unwrap_or_else(Style::empty);
+        if style.contains(Style::REVERSE) {
+            // the colors are swapped after falling back to `Normal`
+            fg = fg.or(normal.and_then(|normal| normal.fg));
+            bg = bg.or(normal.and_then(|normal| normal.bg));
+            std::mem::swap(&mut fg, &mut bg);
+            style.remove(Style::REVERSE);
+        }
+
+        Swatch {
+            hl_group,
+            link,
+            fg,
+            bg,
+            special: format.and_then(|format| resolve(&format.special)),
+            style,
+        }
+    }
+}
+
+/// A line of a sample, see [`SAMPLES`].
+pub(crate) struct SampleLine<'a> {
+    pub line_group: Option<&'a str>,
+    /// The text and its highlight group, `None` for plain text.
+    pub spans: Vec<(Option<&'a str>, &'a str)>,
+}
+
+impl<'a> SampleLine<'a> {
+    pub fn parse(line: &'a str) -> Self {
+        let (line_group, mut rest) =
+            match line.strip_prefix('«').and_then(|line| line.split_once('»')) {
+                Some((group, rest)) if !group.contains(' ') => (Some(group), rest),
+                _ => (None, line),
+            };
+
+        let mut spans = Vec::new();
+        while let Some((text, span)) = rest.split_once('«') {
+            let Some((span, after)) = span.split_once('»') else {
+                break;
+            };
+            if !text.is_empty() {
+                spans.push((None, text));
+            }
+            let (group, text) = span.split_once(' ').unwrap_or((span, ""));
+            spans.push((Some(group), text));
+            rest = after;
+        }
+        if !rest.is_empty() {
+            spans.push((None, rest));
+        }
+
+        SampleLine { line_group, spans }
+    }
+}
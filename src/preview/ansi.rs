@@ -0,0 +1,106 @@
+use crate::{
+    palette::RgbColor,
+    preview::{Preview, Swatch},
+    sections::color_spec::Style,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// Renders a [`Preview`] with truecolor escape sequences, colors that a group doesn't set are
+/// taken from `Normal`.
+pub struct AnsiPreview<'a>(pub &'a Preview<'a>);
+
+impl Display for AnsiPreview<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let preview = self.0;
+
+        writeln!(f, "palette")?;
+        let width = preview.palette.0.keys().map(String::len).max();
+        for (name, color) in &preview.palette.0 {
+            writeln!(
+                f,
+                "  {}      {RESET}  {name:width$}  {color}",
+                Sgr::background(*color),
+                width = width.unwrap_or_default(),
+            )?;
+        }
+
+        for (section_name, swatches) in &preview.sections {
+            writeln!(f, "\n{section_name}")?;
+            let width = swatches.iter().map(|swatch| swatch.hl_group.len()).max();
+            for swatch in swatches {
+                write!(
+                    f,
+                    "  {} {:width$} {RESET}",
+                    Sgr::swatch(swatch, &preview.normal),
+                    swatch.hl_group,
+                    width = width.unwrap_or_default(),
+                )?;
+                match swatch.link {
+                    Some(link) => writeln!(f, "  -> {link}")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// An SGR escape sequence setting colors and text attributes.
+struct Sgr {
+    fg: Option<RgbColor>,
+    bg: Option<RgbColor>,
+    special: Option<RgbColor>,
+    style: Style,
+}
+
+impl Sgr {
+    fn background(color: RgbColor) -> Self {
+        Sgr {
+            fg: None,
+            bg: Some(color),
+            special: None,
+            style: Style::empty(),
+        }
+    }
+
+    fn swatch(swatch: &Swatch, normal: &Swatch) -> Self {
+        Sgr {
+            fg: swatch.fg.or(normal.fg),
+            bg: swatch.bg.or(normal.bg),
+            special: swatch.special,
+            style: swatch.style,
+        }
+    }
+}
+
+/// The SGR parameters of the text attributes, underline styles use the `4:x` extension.
+const STYLE_PARAMETERS: [(Style, &str); 9] = [
+    (Style::BOLD, "1"),
+    (Style::ITALIC, "3"),
+    (Style::UNDERLINE, "4"),
+    (Style::UNDERCURL, "4:3"),
+    (Style::UNDERDOUBLE, "4:2"),
+    (Style::UNDERDOTTED, "4:4"),
+    (Style::UNDERDASHED, "4:5"),
+    (Style::STANDOUT, "7"),
+    (Style::STRIKETHROUGH, "9"),
+];
+
+impl Display for Sgr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut parameters = Vec::new();
+        for (style, parameter) in STYLE_PARAMETERS {
+            if self.style.contains(style) {
+                parameters.push(parameter.to_string());
+            }
+        }
+        for (code, color) in [(38, self.fg), (48, self.bg), (58, self.special)] {
+            if let Some(RgbColor([r, g, b])) = color {
+                parameters.push(format!("{code};2;{r};{g};{b}"));
+            }
+        }
+        write!(f, "\x1b[{}m", parameters.join(";"))
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    extras::textmate::Xml,
+    preview::{Preview, SampleLine, Swatch, SAMPLES},
+    sections::color_spec::Style,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// Renders a [`Preview`] as a self-contained HTML page.
+pub struct HtmlPreview<'a>(pub &'a Preview<'a>);
+
+impl Display for HtmlPreview<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let preview = self.0;
+
+        writeln!(
+            f,
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{name}</title>
+<style>
+body {{ margin: 2em; font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 14px; {normal} }}
+h1, h2, h3 {{ font-weight: normal; }}
+.palette {{ display: grid; grid-template-columns: repeat(auto-fill, minmax(10em, 1fr)); gap: 1em; }}
+.chip {{ height: 3em; border-radius: 4px; margin-bottom: 0.3em; }}
+.code {{ white-space: pre; line-height: 1.5; padding: 0.5em 0; overflow-x: auto; }}
+.code > div {{ min-height: 1.5em; padding-right: 1em; }}
+.groups {{ display: flex; flex-wrap: wrap; gap: 0.3em; }}
+.groups > span {{ padding: 0.2em 0.5em; }}
+</style>
+</head>
+<body>
+<h1>{name}</h1>
+<h2>Palette</h2>
+<div class="palette">"#,
+            name = Xml(preview.name),
+            normal = Css(&preview.normal),
+        )?;
+        for (name, color) in &preview.palette.0 {
+            writeln!(
+                f,
+                r#"<div><div class="chip" style="background-color: {color}"></div>{}<br>{color}</div>"#,
+                Xml(name)
+            )?;
+        }
+        writeln!(f, "</div>")?;
+
+        for (title, line_numbers, sample) in SAMPLES {
+            writeln!(f, "<h2>{title}</h2>\n<div class=\"code\">")?;
+            for (number, line) in sample.lines().enumerate() {
+                let line = SampleLine::parse(line);
+                match line.line_group {
+                    Some(group) => write!(f, "<div style=\"{}\">", Css(&preview.resolve(group)))?,
+                    None => write!(f, "<div>")?,
+                }
+                if *line_numbers {
+                    let swatch = preview.resolve("LineNr");
+                    write!(
+                        f,
+                        "<span style=\"{}\">{:>4} </span>",
+                        Css(&swatch),
+                        number + 1
+                    )?;
+                }
+                for (group, text) in line.spans {
+                    match group {
+                        Some(group) => write!(
+                            f,
+                            "<span style=\"{}\" title=\"{group}\">{}</span>",
+                            Css(&preview.resolve(group)),
+                            Xml(text)
+                        )?,
+                        None => write!(f, "{}", Xml(text))?,
+                    }
+                }
+                writeln!(f, "</div>")?;
+            }
+            writeln!(f, "</div>")?;
+        }
+
+        writeln!(f, "<h2>Highlight groups</h2>")?;
+        for (section_name, swatches) in &preview.sections {
+            writeln!(f, "<h3>{}</h3>\n<div class=\"groups\">", Xml(section_name))?;
+            for swatch in swatches {
+                let title = match swatch.link {
+                    Some(link) => format!("{} -> {link}", swatch.hl_group),
+                    None => swatch.hl_group.to_string(),
+                };
+                writeln!(
+                    f,
+                    "<span style=\"{}\" title=\"{}\">{}</span>",
+                    Css(swatch),
+                    Xml(&title),
+                    Xml(swatch.hl_group)
+                )?;
+            }
+            writeln!(f, "</div>")?;
+        }
+
+        writeln!(f, "</body>\n</html>")
+    }
+}
+
+/// The inline CSS of a [`Swatch`].
+struct Css<'a>(&'a Swatch<'a>);
+
+/// The CSS `text-decoration-style` of the underline styles.
+const UNDERLINES: [(Style, &str); 5] = [
+    (Style::UNDERLINE, "solid"),
+    (Style::UNDERCURL, "wavy"),
+    (Style::UNDERDOUBLE, "double"),
+    (Style::UNDERDOTTED, "dotted"),
+    (Style::UNDERDASHED, "dashed"),
+];
+
+impl Display for Css<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let swatch = self.0;
+        if let Some(fg) = swatch.fg {
+            write!(f, "color: {fg}; ")?;
+        }
+        if let Some(bg) = swatch.bg {
+            write!(f, "background-color: {bg}; ")?;
+        }
+        if swatch.style.contains(Style::BOLD) {
+            write!(f, "font-weight: bold; ")?;
+        }
+        if swatch.style.contains(Style::ITALIC) {
+            write!(f, "font-style: italic; ")?;
+        }
+
+        let underline = UNDERLINES
+            .iter()
+            .find(|(style, _)| swatch.style.contains(*style))
+            .map(|(_, line)| *line);
+        let strikethrough = swatch.style.contains(Style::STRIKETHROUGH);
+        let lines = match (underline.is_some(), strikethrough) {
+            (false, false) => return Ok(()),
+            (true, false) => "underline",
+            (false, true) => "line-through",
+            (true, true) => "underline line-through",
+        };
+        write!(
+            f,
+            "text-decoration: {lines} {}",
+            underline.unwrap_or("solid")
+        )?;
+        if let Some(special) = swatch.special {
+            write!(f, " {special}")?;
+        }
+        write!(f, "; ")
+    }
+}
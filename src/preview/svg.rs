@@ -0,0 +1,298 @@
+use crate::{
+    extras::textmate::Xml,
+    palette::RgbColor,
+    preview::{Preview, SampleLine, Swatch},
+    sections::color_spec::Style,
+};
+use resvg::{tiny_skia, usvg};
+use std::fmt::{self, Display, Formatter};
+
+/// DejaVu Sans Mono, bundled so screenshots look the same everywhere.
+const FONTS: [&[u8]; 4] = [
+    include_bytes!("fonts/DejaVuSansMono.ttf"),
+    include_bytes!("fonts/DejaVuSansMono-Bold.ttf"),
+    include_bytes!("fonts/DejaVuSansMono-Oblique.ttf"),
+    include_bytes!("fonts/DejaVuSansMono-BoldOblique.ttf"),
+];
+const FONT_FAMILY: &str = "DejaVu Sans Mono";
+const FONT_SIZE: f64 = 14.0;
+/// The advance of every glyph of DejaVu Sans Mono, 1233/2048 em.
+const CELL_WIDTH: f64 = FONT_SIZE * 1233.0 / 2048.0;
+const LINE_HEIGHT: f64 = 20.0;
+/// The baseline within a line.
+const BASELINE: f64 = 15.0;
+
+const COLUMNS: usize = 80;
+/// The lines of the window, the statusline and the command line come below.
+const LINES: usize = 24;
+
+/// The code in the window.
+const SAMPLE: &str = include_str!("samples/rust.txt");
+/// The line (starting at 1) and column (starting at 0) of the cursor.
+const CURSOR: (usize, usize) = (10, 15);
+/// The line with an error sign and its virtual text.
+const DIAGNOSTIC: (usize, &str) = (11, "■ cannot borrow as mutable");
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScreenshotError {
+    #[error(transparent)]
+    Svg(#[from] usvg::Error),
+    #[error("Failed to write the PNG: {0}")]
+    Png(String),
+}
+
+/// Draws a [`Preview`] as an editor window with line numbers, a sign column, the cursor line and
+/// a statusline, as SVG.
+pub struct SvgScreenshot<'a>(pub &'a Preview<'a>);
+
+impl Display for SvgScreenshot<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let preview = self.0;
+        let normal = &preview.normal;
+        let width = COLUMNS as f64 * CELL_WIDTH;
+        let height = (LINES + 2) as f64 * LINE_HEIGHT;
+
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.2} {height:.2}" font-family="{FONT_FAMILY}, monospace" font-size="{FONT_SIZE}" shape-rendering="crispEdges" xml:space="preserve">"#
+        )?;
+
+        let sign_column = preview.resolve("SignColumn").over(normal);
+        for (index, line) in SAMPLE.lines().take(LINES).enumerate() {
+            let number = index + 1;
+            let line_swatch = match number == CURSOR.0 {
+                true => preview.resolve("CursorLine").over(normal),
+                false => normal.clone(),
+            };
+            let (sign, sign_swatch) = match number == DIAGNOSTIC.0 {
+                true => ("E ", preview.resolve("DiagnosticSignError")),
+                false => ("  ", sign_column.clone()),
+            };
+            let line_nr = match number == CURSOR.0 {
+                true => "CursorLineNr",
+                false => "LineNr",
+            };
+
+            let mut spans = vec![
+                (sign_swatch.over(&sign_column), sign.to_string()),
+                (
+                    preview.resolve(line_nr).over(&line_swatch),
+                    format!("{number:>3} "),
+                ),
+            ];
+            for (group, text) in SampleLine::parse(line).spans {
+                let swatch = match group {
+                    Some(group) => preview.resolve(group).over(&line_swatch),
+                    None => line_swatch.clone(),
+                };
+                spans.push((swatch, text.to_string()));
+            }
+            if number == DIAGNOSTIC.0 {
+                spans.push((line_swatch.clone(), "  ".to_string()));
+                spans.push((
+                    preview
+                        .resolve("DiagnosticVirtualTextError")
+                        .over(&line_swatch),
+                    DIAGNOSTIC.1.to_string(),
+                ));
+            }
+
+            write_line(f, index, &line_swatch, &spans)?;
+            if number == CURSOR.0 {
+                write_cursor(f, preview, index, &spans)?;
+            }
+        }
+
+        let lines = SAMPLE.lines().count().min(LINES);
+        let end_of_buffer = preview.resolve("EndOfBuffer").over(normal);
+        for index in lines..LINES {
+            write_line(
+                f,
+                index,
+                normal,
+                &[(end_of_buffer.clone(), "~".to_string())],
+            )?;
+        }
+
+        let status_line = preview.resolve("StatusLine").over(normal);
+        let left = " NORMAL  src/words.rs [+]";
+        let right = format!("rust  {}:{} ", CURSOR.0, CURSOR.1 + 1);
+        let status = format!("{left}{right:>width$}", width = COLUMNS - left.len());
+        write_line(f, LINES, &status_line, &[(status_line.clone(), status)])?;
+
+        let message = preview.resolve("MsgArea").over(normal);
+        let written = format!("\"src/words.rs\" {lines}L written");
+        write_line(f, LINES + 1, &message, &[(message.clone(), written)])?;
+
+        writeln!(f, "</svg>")
+    }
+}
+
+/// Draws a line of cells, the background of `swatch` fills the whole line.
+fn write_line(
+    f: &mut Formatter<'_>,
+    index: usize,
+    swatch: &Swatch,
+    spans: &[(Swatch, String)],
+) -> fmt::Result {
+    let y = index as f64 * LINE_HEIGHT;
+    if let Some(bg) = swatch.bg {
+        writeln!(
+            f,
+            r#"<rect x="0" y="{y}" width="100%" height="{LINE_HEIGHT}" fill="{bg}"/>"#
+        )?;
+    }
+
+    let mut column = 0;
+    for (span, text) in spans {
+        let len = text.chars().count();
+        if span.bg != swatch.bg {
+            write_rect(f, column, len, y, span.bg)?;
+        }
+        write_text(f, column, y, span, text)?;
+        column += len;
+    }
+    Ok(())
+}
+
+fn write_rect(
+    f: &mut Formatter<'_>,
+    column: usize,
+    len: usize,
+    y: f64,
+    color: Option<RgbColor>,
+) -> fmt::Result {
+    match color {
+        Some(color) => writeln!(
+            f,
+            r#"<rect x="{:.2}" y="{y}" width="{:.2}" height="{LINE_HEIGHT}" fill="{color}"/>"#,
+            column as f64 * CELL_WIDTH,
+            len as f64 * CELL_WIDTH,
+        ),
+        None => Ok(()),
+    }
+}
+
+fn write_text(
+    f: &mut Formatter<'_>,
+    column: usize,
+    y: f64,
+    swatch: &Swatch,
+    text: &str,
+) -> fmt::Result {
+    let trimmed = text.trim_start();
+    if trimmed.trim_end().is_empty() {
+        return Ok(());
+    }
+    let column = column + text.chars().count() - trimmed.chars().count();
+
+    write!(
+        f,
+        r#"<text x="{:.2}" y="{}""#,
+        column as f64 * CELL_WIDTH,
+        y + BASELINE
+    )?;
+    if let Some(fg) = swatch.fg {
+        write!(f, r#" fill="{fg}""#)?;
+    }
+    if swatch.style.contains(Style::BOLD) {
+        write!(f, r#" font-weight="bold""#)?;
+    }
+    if swatch.style.contains(Style::ITALIC) {
+        write!(f, r#" font-style="italic""#)?;
+    }
+    let underline = Style::UNDERLINE
+        | Style::UNDERCURL
+        | Style::UNDERDOUBLE
+        | Style::UNDERDOTTED
+        | Style::UNDERDASHED;
+    match (
+        swatch.style.intersects(underline),
+        swatch.style.contains(Style::STRIKETHROUGH),
+    ) {
+        (true, true) => write!(f, r#" text-decoration="underline line-through""#)?,
+        (true, false) => write!(f, r#" text-decoration="underline""#)?,
+        (false, true) => write!(f, r#" text-decoration="line-through""#)?,
+        (false, false) => (),
+    }
+    writeln!(f, ">{}</text>", Xml(trimmed.trim_end()))
+}
+
+/// Draws the cursor as a block over the character below it.
+fn write_cursor(
+    f: &mut Formatter<'_>,
+    preview: &Preview,
+    index: usize,
+    spans: &[(Swatch, String)],
+) -> fmt::Result {
+    let column = CURSOR.1 + 6;
+    let character = spans
+        .iter()
+        .flat_map(|(_, text)| text.chars())
+        .nth(column)
+        .unwrap_or(' ');
+
+    // like Neovim, an unset cursor is drawn in reverse
+    let normal = &preview.normal;
+    let mut cursor = preview.resolve("Cursor");
+    if cursor.fg.is_none() && cursor.bg.is_none() {
+        cursor.fg = normal.bg;
+        cursor.bg = normal.fg;
+    }
+    let cursor = cursor.over(normal);
+
+    let y = index as f64 * LINE_HEIGHT;
+    write_rect(f, column, 1, y, cursor.bg)?;
+    write_text(f, column, y, &cursor, &character.to_string())
+}
+
+/// Rasterizes an SVG written by [`SvgScreenshot`] with the bundled font.
+pub fn render_png(svg: &str, scale: f32) -> Result<Vec<u8>, ScreenshotError> {
+    let mut options = usvg::Options::default();
+    for font in FONTS {
+        options.fontdb_mut().load_font_data(font.to_vec());
+    }
+    let tree = usvg::Tree::from_str(svg, &options)?;
+
+    let size = tree.size().to_int_size().scale_by(scale);
+    let mut pixmap = size
+        .and_then(|size| tiny_skia::Pixmap::new(size.width(), size.height()))
+        .ok_or_else(|| ScreenshotError::Png(format!("can't scale the image by {scale}")))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|err| ScreenshotError::Png(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Text<'a>(usize, &'a str);
+
+    impl Display for Text<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            let swatch = Swatch {
+                hl_group: "Normal",
+                link: None,
+                fg: None,
+                bg: None,
+                special: None,
+                style: Style::empty(),
+            };
+            write_text(f, self.0, 0.0, &swatch, self.1)
+        }
+    }
+
+    /// Leading whitespace is offset by cells, not bytes.
+    #[test]
+    fn text_starts_after_its_indent() {
+        let x = |column: usize| format!(r#"<text x="{:.2}""#, column as f64 * CELL_WIDTH);
+        assert!(Text(4, "  word").to_string().starts_with(&x(6)));
+        assert!(Text(0, "\u{a0}\u{a0}·").to_string().starts_with(&x(2)));
+    }
+}
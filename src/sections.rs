@@ -1,11 +1,15 @@
-use crate::palette::Palette;
-use crate::sections::color_spec::{parser::Color, ColorFormat};
+use crate::sections::color_spec::{parser::Color, ColorFormat, HighlightFormatter};
+use crate::{palette::Palette, terminal::TerminalColors};
 use color_spec::ColorSpec;
 use linked_hash_map::LinkedHashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
-mod color_spec;
+pub mod color_spec;
+pub mod semantic_tokens;
+
+/// Link chains longer than this are treated as cycles.
+const MAX_LINK_DEPTH: usize = 32;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Sections(pub LinkedHashMap<String, Section>);
@@ -17,15 +21,13 @@ impl Sections {
     pub fn check_colors(&self, palette: &Palette) -> Result<(), MissingPaletteColor> {
         for (section_name, section) in &self.0 {
             for (hl_group, color_spec) in &section.0 {
-                match color_spec {
-                    ColorSpec::Color(ColorFormat {
-                        fg, bg, special, ..
-                    }) => {
-                        check_palette_ref(fg, palette, section_name, hl_group)?;
-                        check_palette_ref(bg, palette, section_name, hl_group)?;
-                        check_palette_ref(special, palette, section_name, hl_group)?;
-                    }
-                    _ => (),
+                if let ColorSpec::Color(ColorFormat {
+                    fg, bg, special, ..
+                }) = color_spec
+                {
+                    check_palette_ref(fg, palette, section_name, hl_group)?;
+                    check_palette_ref(bg, palette, section_name, hl_group)?;
+                    check_palette_ref(special, palette, section_name, hl_group)?;
                 }
             }
         }
@@ -34,6 +36,31 @@ impl Sections {
     }
 }
 
+impl Sections {
+    /// Finds the highlight group, following `link:` specs.
+    ///
+    /// When a group is defined in several sections the last definition wins, like it does in
+    /// Neovim.
+    pub fn resolve(&self, hl_group: &str) -> Option<&ColorFormat> {
+        let mut hl_group = hl_group;
+        for _ in 0..MAX_LINK_DEPTH {
+            match self.get(hl_group)? {
+                ColorSpec::Color(format) => return Some(format),
+                ColorSpec::Link(link) => hl_group = link,
+            }
+        }
+        None
+    }
+
+    /// Finds the color spec of a highlight group without following links.
+    pub fn get(&self, hl_group: &str) -> Option<&ColorSpec> {
+        self.0
+            .values()
+            .rev()
+            .find_map(|section| section.0.get(hl_group))
+    }
+}
+
 pub fn check_palette_ref(
     color: &Option<Color>,
     palette: &Palette,
@@ -57,7 +84,7 @@ pub fn check_palette_ref(
 }
 
 #[derive(Debug, thiserror::Error)]
-#[error("In highlight group {section_name}.{highlight_group} the palette color {color_ref} could not be found")]
+#[error("In {section_name}.{highlight_group} the palette color {color_ref} could not be found")]
 pub struct MissingPaletteColor {
     section_name: String,
     highlight_group: String,
@@ -67,6 +94,9 @@ pub struct MissingPaletteColor {
 pub struct SectionsFormatter<'a> {
     pub theme_name: &'a str,
     pub sections: &'a Sections,
+    pub terminal: TerminalColors<'a>,
+    /// Also emit cterm attributes, see [`HighlightFormatter`].
+    pub cterm: Option<&'a Palette>,
 }
 
 impl<'a> Display for SectionsFormatter<'a> {
@@ -89,10 +119,13 @@ theme.set_highlights = function()",
             ThemeHighlights {
                 theme_name: self.theme_name,
                 sections: self.sections,
-                indent: "  "
+                indent: "  ",
+                cterm: self.cterm,
             }
         )?;
 
+        write!(f, "{}", self.terminal)?;
+
         write!(f, "\n\nend\n\nreturn theme")
     }
 }
@@ -101,6 +134,8 @@ pub struct ThemeHighlights<'a> {
     pub theme_name: &'a str,
     pub sections: &'a Sections,
     pub indent: &'a str,
+    /// Also emit cterm attributes, see [`HighlightFormatter`].
+    pub cterm: Option<&'a Palette>,
 }
 
 impl Display for ThemeHighlights<'_> {
@@ -111,7 +146,11 @@ impl Display for ThemeHighlights<'_> {
                 write!(
                     f,
                     "\n{indent}hl(0, \"{hl_group}\", {color_spec})",
-                    indent = self.indent
+                    indent = self.indent,
+                    color_spec = HighlightFormatter {
+                        color_spec,
+                        cterm: self.cterm,
+                    }
                 )?;
             }
         }
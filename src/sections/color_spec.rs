@@ -1,4 +1,5 @@
 use self::parser::Color;
+use crate::palette::Palette;
 use bitflags::bitflags;
 use std::fmt::Display;
 
@@ -12,10 +13,39 @@ pub enum ColorSpec {
 
 impl Display for ColorSpec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ColorSpec::Link(link) => write!(f, "{{ link = '{link}' }}"),
-            ColorSpec::Color(color) => write!(f, "{color}"),
+        write!(
+            f,
+            "{}",
+            HighlightFormatter {
+                color_spec: self,
+                cterm: None,
+            }
+        )
+    }
+}
+
+/// Formats the attribute table passed to `nvim_set_hl`.
+///
+/// If `cterm` is set the colors are also resolved against the palette to emit their closest
+/// xterm-256 colors as `ctermfg`/`ctermbg`, together with the style as `cterm`.
+pub struct HighlightFormatter<'a> {
+    pub color_spec: &'a ColorSpec,
+    pub cterm: Option<&'a Palette>,
+}
+
+impl Display for HighlightFormatter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let color = match self.color_spec {
+            ColorSpec::Link(link) => return write!(f, "{{ link = '{link}' }}"),
+            ColorSpec::Color(color) => color,
+        };
+
+        write!(f, "{{")?;
+        color.fmt_attributes(f)?;
+        if let Some(palette) = self.cterm {
+            color.fmt_cterm_attributes(f, palette)?;
         }
+        write!(f, " }}")
     }
 }
 
@@ -31,7 +61,13 @@ pub struct ColorFormat {
 impl Display for ColorFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{{")?;
+        self.fmt_attributes(f)?;
+        write!(f, " }}")
+    }
+}
 
+impl ColorFormat {
+    fn fmt_attributes(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(fg) = &self.fg {
             write!(f, " fg = {fg},")?;
         } else {
@@ -56,7 +92,26 @@ impl Display for ColorFormat {
             write!(f, "{style}")?;
         }
 
-        write!(f, " }}")?;
+        Ok(())
+    }
+
+    fn fmt_cterm_attributes(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        palette: &Palette,
+    ) -> std::fmt::Result {
+        if let Some(fg) = self.fg.as_ref().and_then(|fg| fg.resolve(palette)) {
+            write!(f, " ctermfg = {},", fg.to_xterm256())?;
+        }
+
+        if let Some(bg) = self.bg.as_ref().and_then(|bg| bg.resolve(palette)) {
+            write!(f, " ctermbg = {},", bg.to_xterm256())?;
+        }
+
+        if let Some(style) = self.style.filter(|style| !style.is_empty()) {
+            write!(f, " cterm = {{{style} }},")?;
+        }
+
         Ok(())
     }
 }
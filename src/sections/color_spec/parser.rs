@@ -1,5 +1,5 @@
 use crate::{
-    palette::RgbColor,
+    palette::{Palette, RgbColor},
     sections::color_spec::{ColorFormat, ColorSpec, Style},
 };
 use hex::FromHexError;
@@ -7,7 +7,7 @@ use nom::{
     branch::alt,
     bytes::complete::{is_a, tag, take, take_till},
     character::complete::digit1,
-    combinator::{eof, map_res, rest},
+    combinator::{all_consuming, eof, map_res, rest},
     sequence::{pair, tuple},
     Finish, IResult, Parser,
 };
@@ -15,6 +15,8 @@ use std::{fmt::Display, num::ParseIntError, str::FromStr};
 
 mod serde;
 
+pub use self::serde::LongForm;
+
 impl FromStr for ColorSpec {
     type Err = ParseColorSpecError;
 
@@ -29,7 +31,7 @@ impl FromStr for ColorSpec {
 
 #[derive(Debug, thiserror::Error)]
 #[error(r#"failed to parse "{0}" with error "{1}""#)]
-pub struct ParseColorSpecError(String, String);
+pub struct ParseColorSpecError(pub(crate) String, pub(crate) String);
 
 fn parse_color_spec(input: &str) -> IResult<&str, ColorSpec> {
     let (input, color_spec) = alt((
@@ -39,7 +41,7 @@ fn parse_color_spec(input: &str) -> IResult<&str, ColorSpec> {
             alt((
                 eof.map(|_| None),
                 tag("-").map(|_| None),
-                parse_value.map(|color| Some(color)),
+                parse_value.map(Some),
             )), // fg
             alt((
                 eof.map(|_| None),
@@ -110,6 +112,16 @@ impl Display for Color {
     }
 }
 
+impl Color {
+    /// Looks up palette references, `None` if the palette doesn't contain the color.
+    pub fn resolve(&self, palette: &Palette) -> Option<RgbColor> {
+        match self {
+            Color::Color(rgb_color) => Some(*rgb_color),
+            Color::PaletteRef(palette_ref) => palette.0.get(palette_ref).copied(),
+        }
+    }
+}
+
 fn parse_value(input: &str) -> IResult<&str, Color> {
     let (input, color) = alt((
         pair(
@@ -127,26 +139,121 @@ fn parse_value(input: &str) -> IResult<&str, Color> {
     Ok((input, color))
 }
 
+/// The letters accepted in the style position of a color spec, in canonical order.
+pub(crate) const STYLE_LETTERS: [(char, Style); 11] = [
+    ('o', Style::STANDOUT),
+    ('u', Style::UNDERLINE),
+    ('c', Style::UNDERCURL),
+    ('d', Style::UNDERDOUBLE),
+    ('t', Style::UNDERDOTTED),
+    ('h', Style::UNDERDASHED),
+    ('s', Style::STRIKETHROUGH),
+    ('i', Style::ITALIC),
+    ('b', Style::BOLD),
+    ('r', Style::REVERSE),
+    ('n', Style::NOCOMBINE),
+];
+
 fn parse_style(input: &str) -> IResult<&str, Style> {
     let (input, style_str) = is_a("oucdthsibrn")(input)?;
     Ok((
         input,
         style_str
             .chars()
-            .map(|c| match c {
-                'o' => Style::STANDOUT,
-                'u' => Style::UNDERLINE,
-                'c' => Style::UNDERCURL,
-                'd' => Style::UNDERDOUBLE,
-                't' => Style::UNDERDOTTED,
-                'h' => Style::UNDERDASHED,
-                's' => Style::STRIKETHROUGH,
-                'i' => Style::ITALIC,
-                'b' => Style::BOLD,
-                'r' => Style::REVERSE,
-                'n' => Style::NOCOMBINE,
-                _ => unreachable!("The parser filters other characters out"),
+            .map(|c| {
+                STYLE_LETTERS
+                    .iter()
+                    .find(|(letter, _)| *letter == c)
+                    .map(|(_, style)| *style)
+                    .expect("The parser filters other characters out")
             })
             .collect(),
     ))
 }
+
+impl FromStr for Color {
+    type Err = ParseColorSpecError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, color) = all_consuming(parse_value)(input)
+            .finish()
+            .map_err(|err| ParseColorSpecError(input.to_string(), err.to_string()))?;
+
+        Ok(color)
+    }
+}
+
+impl FromStr for Style {
+    type Err = ParseColorSpecError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (_, style) = all_consuming(parse_style)(input)
+            .finish()
+            .map_err(|err| ParseColorSpecError(input.to_string(), err.to_string()))?;
+
+        Ok(style)
+    }
+}
+
+/// Formats a [`ColorSpec`] in the short template form, e.g. `fg bg bi - 50` or `link:Comment`.
+///
+/// Trailing `-` placeholders are dropped, so the output is the canonical spelling of the spec.
+pub struct SpecString<'a>(pub &'a ColorSpec);
+
+impl Display for SpecString<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = match self.0 {
+            ColorSpec::Link(link) => return write!(f, "link:{link}"),
+            ColorSpec::Color(format) => format,
+        };
+
+        let fields = [
+            format.fg.as_ref().map(|fg| SpecColor(fg).to_string()),
+            format.bg.as_ref().map(|bg| SpecColor(bg).to_string()),
+            format
+                .style
+                .filter(|style| !style.is_empty())
+                .map(|style| SpecStyle(style).to_string()),
+            format.special.as_ref().map(|sp| SpecColor(sp).to_string()),
+            format.blend.map(|blend| blend.to_string()),
+        ];
+        let len = fields
+            .iter()
+            .rposition(Option::is_some)
+            .map_or(1, |last| last + 1);
+
+        for (i, field) in fields[..len].iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", field.as_deref().unwrap_or("-"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a [`Color`] the way it is written in a template: `#rrggbb` or a palette key.
+pub struct SpecColor<'a>(pub &'a Color);
+
+impl Display for SpecColor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Color::Color(rgb_color) => write!(f, "{rgb_color}"),
+            Color::PaletteRef(palette_ref) => write!(f, "{palette_ref}"),
+        }
+    }
+}
+
+/// Formats a [`Style`] as its template letters in canonical order.
+pub struct SpecStyle(pub Style);
+
+impl Display for SpecStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (letter, style) in STYLE_LETTERS {
+            if self.0.contains(style) {
+                write!(f, "{letter}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,12 +1,59 @@
-use crate::sections::color_spec::ColorSpec;
-use serde::{de::Visitor, Deserialize, Serialize};
+use crate::sections::color_spec::{
+    parser::{Color, ParseColorSpecError, SpecColor, SpecString, SpecStyle},
+    ColorFormat, ColorSpec, Style,
+};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Serialize,
+};
+use std::str::FromStr;
 
 impl Serialize for ColorSpec {
-    fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        unimplemented!()
+        serializer.collect_str(&SpecString(self))
+    }
+}
+
+/// Serializes a [`ColorSpec`] in the long table form, e.g. `{ fg = "red", style = "bi" }`.
+///
+/// Both forms are accepted when deserializing.
+pub struct LongForm<'a>(pub &'a ColorSpec);
+
+impl Serialize for LongForm<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let format = match self.0 {
+            ColorSpec::Link(link) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("link", link)?;
+                return map.end();
+            }
+            ColorSpec::Color(format) => format,
+        };
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(fg) = &format.fg {
+            map.serialize_entry("fg", &SpecColor(fg).to_string())?;
+        }
+        if let Some(bg) = &format.bg {
+            map.serialize_entry("bg", &SpecColor(bg).to_string())?;
+        }
+        if let Some(style) = format.style.filter(|style| !style.is_empty()) {
+            map.serialize_entry("style", &SpecStyle(style).to_string())?;
+        }
+        if let Some(special) = &format.special {
+            map.serialize_entry("sp", &SpecColor(special).to_string())?;
+        }
+        if let Some(blend) = format.blend {
+            map.serialize_entry("blend", &blend)?;
+        }
+        map.end()
     }
 }
 
@@ -15,7 +62,7 @@ impl<'de> Deserialize<'de> for ColorSpec {
     where
         D: serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(ColorSpecVisitor)
+        deserializer.deserialize_any(ColorSpecVisitor)
     }
 }
 
@@ -25,14 +72,206 @@ impl<'de> Visitor<'de> for ColorSpecVisitor {
     type Value = ColorSpec;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter
-            .write_str("Expected a str in the form of `foreground background style special blend`")
+        formatter.write_str(
+            "Expected a str in the form of `foreground background style special blend` or a table",
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let table = ColorSpecTable::deserialize(MapAccessDeserializer::new(map))?;
+        table.try_into().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorSpecTable {
+    link: Option<String>,
+    fg: Option<String>,
+    bg: Option<String>,
+    style: Option<String>,
+    sp: Option<String>,
+    blend: Option<u8>,
+}
+
+impl TryFrom<ColorSpecTable> for ColorSpec {
+    type Error = String;
+
+    fn try_from(table: ColorSpecTable) -> Result<Self, Self::Error> {
+        let ColorSpecTable {
+            link,
+            fg,
+            bg,
+            style,
+            sp,
+            blend,
+        } = table;
+
+        if let Some(link) = link {
+            if fg.is_some() || bg.is_some() || style.is_some() || sp.is_some() || blend.is_some() {
+                return Err(format!(
+                    "`link = '{link}'` can't be combined with other keys"
+                ));
+            }
+            return Ok(ColorSpec::Link(link));
+        }
+
+        if let Some(blend) = blend.filter(|blend| *blend > 100) {
+            return Err(format!(
+                "expected blend to be between 0 and 100, found {blend}"
+            ));
+        }
+
+        Ok(ColorSpec::Color(ColorFormat {
+            fg: parse_field(fg)?,
+            bg: parse_field(bg)?,
+            style: parse_field(style)?,
+            special: parse_field(sp)?,
+            blend,
+        }))
+    }
+}
+
+fn parse_field<T>(value: Option<String>) -> Result<Option<T>, String>
+where
+    T: FromStr<Err = ParseColorSpecError>,
+{
+    value
+        .filter(|value| value != "-")
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|err: ParseColorSpecError| err.to_string())
+        })
+        .transpose()
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&SpecColor(self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+struct ColorVisitor;
+
+impl<'de> Visitor<'de> for ColorVisitor {
+    type Value = Color;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a hex color in the form #ff0000 or the name of a palette color")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
-        E: serde::de::Error,
+        E: de::Error,
     {
         v.parse().map_err(E::custom)
     }
 }
+
+impl Serialize for Style {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&SpecStyle(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(StyleVisitor)
+    }
+}
+
+struct StyleVisitor;
+
+impl<'de> Visitor<'de> for StyleVisitor {
+    type Value = Style;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("style letters like `bi`")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPECS: &[&str] = &[
+        "link:Comment",
+        "fg",
+        "- bg",
+        "#ff0000 #00ff00 bicu",
+        "fg - s red",
+        "fg bg ochdtsirnbu - 50",
+        "- - - - 0",
+    ];
+
+    #[test]
+    fn spec_string_round_trips() {
+        for spec in SPECS {
+            let parsed: ColorSpec = spec.parse().unwrap();
+            let serialized = toml::Value::try_from(&parsed).unwrap();
+            let reparsed = ColorSpec::deserialize(serialized).unwrap();
+            assert_eq!(
+                SpecString(&reparsed).to_string(),
+                SpecString(&parsed).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn long_form_round_trips() {
+        for spec in SPECS {
+            let parsed: ColorSpec = spec.parse().unwrap();
+            let serialized = toml::Value::try_from(LongForm(&parsed)).unwrap();
+            assert!(serialized.is_table());
+            let reparsed = ColorSpec::deserialize(serialized).unwrap();
+            assert_eq!(
+                SpecString(&reparsed).to_string(),
+                SpecString(&parsed).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn spec_string_is_canonical() {
+        let parsed: ColorSpec = "fg - - -".parse().unwrap();
+        assert_eq!(SpecString(&parsed).to_string(), "fg");
+        let parsed: ColorSpec = "fg bg bi".parse().unwrap();
+        assert_eq!(SpecString(&parsed).to_string(), "fg bg ib");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::sections::{color_spec::ColorSpec, Section};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+
+/// The standard LSP semantic token types and the Treesitter captures Neovim links them to.
+const TOKEN_CAPTURES: &[(&str, &str)] = &[
+    ("class", "@type"),
+    ("comment", "@comment"),
+    ("decorator", "@attribute"),
+    ("enum", "@type"),
+    ("enumMember", "@constant"),
+    ("event", "@type"),
+    ("function", "@function"),
+    ("interface", "@type"),
+    ("keyword", "@keyword"),
+    ("macro", "@constant.macro"),
+    ("method", "@function.method"),
+    ("modifier", "@keyword.modifier"),
+    ("namespace", "@module"),
+    ("number", "@number"),
+    ("operator", "@operator"),
+    ("parameter", "@variable.parameter"),
+    ("property", "@property"),
+    ("regexp", "@string.regexp"),
+    ("string", "@string"),
+    ("struct", "@type"),
+    ("type", "@type"),
+    ("typeParameter", "@type.definition"),
+    ("variable", "@variable"),
+];
+
+/// The `[semantic_tokens]` section, e.g. `type.interface = "cyan - i"` for `@lsp.type.interface`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SemanticTokens {
+    /// Link the token types that aren't set here to their Treesitter captures, so semantic tokens
+    /// don't override the Treesitter colors.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link_treesitter: bool,
+    /// `@lsp.type.<type>`
+    #[serde(
+        default,
+        rename = "type",
+        skip_serializing_if = "LinkedHashMap::is_empty"
+    )]
+    pub types: LinkedHashMap<String, ColorSpec>,
+    /// `@lsp.mod.<modifier>`
+    #[serde(
+        default,
+        rename = "mod",
+        skip_serializing_if = "LinkedHashMap::is_empty"
+    )]
+    pub modifiers: LinkedHashMap<String, ColorSpec>,
+    /// `@lsp.typemod.<type>.<modifier>`
+    #[serde(
+        default,
+        rename = "typemod",
+        skip_serializing_if = "LinkedHashMap::is_empty"
+    )]
+    pub type_modifiers: LinkedHashMap<String, LinkedHashMap<String, ColorSpec>>,
+}
+
+impl SemanticTokens {
+    /// Expands the tokens into their `@lsp.*` highlight groups.
+    pub fn into_section(self) -> Section {
+        let mut section = LinkedHashMap::new();
+
+        let captures = if self.link_treesitter {
+            TOKEN_CAPTURES
+        } else {
+            &[]
+        };
+        let links = captures
+            .iter()
+            .filter(|(token, _)| !self.types.contains_key(*token))
+            .map(|(token, capture)| (*token, ColorSpec::Link(capture.to_string())))
+            .collect::<Vec<_>>();
+
+        for (token, color_spec) in self.types {
+            section.insert(format!("@lsp.type.{token}"), color_spec);
+        }
+        for (modifier, color_spec) in self.modifiers {
+            section.insert(format!("@lsp.mod.{modifier}"), color_spec);
+        }
+        for (token, modifiers) in self.type_modifiers {
+            for (modifier, color_spec) in modifiers {
+                section.insert(format!("@lsp.typemod.{token}.{modifier}"), color_spec);
+            }
+        }
+        for (token, link) in links {
+            section.insert(format!("@lsp.type.{token}"), link);
+        }
+
+        Section(section)
+    }
+}
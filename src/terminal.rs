@@ -0,0 +1,166 @@
+use crate::{
+    palette::Palette,
+    sections::{check_palette_ref, color_spec::parser::Color, MissingPaletteColor},
+};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// The `[terminal]` section, mapping the 16 ANSI colors to palette colors.
+///
+/// Colors that aren't listed are looked up in the palette by name, see [`AnsiColor::defaults`],
+/// templates without the section only use those defaults.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Terminal(pub LinkedHashMap<AnsiColor, Color>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl AnsiColor {
+    pub const ALL: [AnsiColor; 16] = [
+        AnsiColor::Black,
+        AnsiColor::Red,
+        AnsiColor::Green,
+        AnsiColor::Yellow,
+        AnsiColor::Blue,
+        AnsiColor::Magenta,
+        AnsiColor::Cyan,
+        AnsiColor::White,
+        AnsiColor::BrightBlack,
+        AnsiColor::BrightRed,
+        AnsiColor::BrightGreen,
+        AnsiColor::BrightYellow,
+        AnsiColor::BrightBlue,
+        AnsiColor::BrightMagenta,
+        AnsiColor::BrightCyan,
+        AnsiColor::BrightWhite,
+    ];
+
+    /// The index of the color in `terminal_color_{index}`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The key of the color in `[terminal]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            AnsiColor::Black => "black",
+            AnsiColor::Red => "red",
+            AnsiColor::Green => "green",
+            AnsiColor::Yellow => "yellow",
+            AnsiColor::Blue => "blue",
+            AnsiColor::Magenta => "magenta",
+            AnsiColor::Cyan => "cyan",
+            AnsiColor::White => "white",
+            AnsiColor::BrightBlack => "bright_black",
+            AnsiColor::BrightRed => "bright_red",
+            AnsiColor::BrightGreen => "bright_green",
+            AnsiColor::BrightYellow => "bright_yellow",
+            AnsiColor::BrightBlue => "bright_blue",
+            AnsiColor::BrightMagenta => "bright_magenta",
+            AnsiColor::BrightCyan => "bright_cyan",
+            AnsiColor::BrightWhite => "bright_white",
+        }
+    }
+
+    /// Palette colors tried in order when the color isn't set in `[terminal]`.
+    pub fn defaults(self) -> &'static [&'static str] {
+        match self {
+            AnsiColor::Black => &["black", "bg"],
+            AnsiColor::Red => &["red"],
+            AnsiColor::Green => &["green"],
+            AnsiColor::Yellow => &["yellow"],
+            AnsiColor::Blue => &["blue"],
+            AnsiColor::Magenta => &["magenta", "purple"],
+            AnsiColor::Cyan => &["cyan"],
+            AnsiColor::White => &["white", "fg"],
+            AnsiColor::BrightBlack => &["bright_black", "gray", "black", "bg"],
+            AnsiColor::BrightRed => &["bright_red", "light_red", "red"],
+            AnsiColor::BrightGreen => &["bright_green", "light_green", "green"],
+            AnsiColor::BrightYellow => &["bright_yellow", "light_yellow", "yellow"],
+            AnsiColor::BrightBlue => &["bright_blue", "light_blue", "blue"],
+            AnsiColor::BrightMagenta => &[
+                "bright_magenta",
+                "light_magenta",
+                "magenta",
+                "light_purple",
+                "purple",
+            ],
+            AnsiColor::BrightCyan => &["bright_cyan", "light_cyan", "cyan"],
+            AnsiColor::BrightWhite => &["bright_white", "light_gray", "white", "fg"],
+        }
+    }
+}
+
+impl Terminal {
+    /// Resolves every ANSI color, falling back to [`AnsiColor::defaults`].
+    ///
+    /// Colors without a fallback in the palette are `None`.
+    pub fn resolve(&self, palette: &Palette) -> [Option<Color>; 16] {
+        AnsiColor::ALL.map(|ansi| {
+            self.0.get(&ansi).cloned().or_else(|| {
+                ansi.defaults()
+                    .iter()
+                    .find(|name| palette.0.contains_key(**name))
+                    .map(|name| Color::PaletteRef(name.to_string()))
+            })
+        })
+    }
+
+    /// Checks that the palette contains the colors the ANSI colors are mapped to.
+    pub fn check_colors(&self, palette: &Palette) -> Result<(), MissingPaletteColor> {
+        for (ansi, color) in &self.0 {
+            check_palette_ref(&Some(color.clone()), palette, "terminal", ansi.name())?;
+        }
+        Ok(())
+    }
+}
+
+pub struct TerminalColors<'a> {
+    /// `None` if the template has no `[terminal]` section.
+    pub terminal: Option<&'a Terminal>,
+    pub palette: &'a Palette,
+    pub indent: &'a str,
+}
+
+impl Display for TerminalColors<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let colors = match self.terminal {
+            Some(terminal) => terminal.resolve(self.palette),
+            None => Terminal::default().resolve(self.palette),
+        };
+        if colors.iter().all(Option::is_none) {
+            return Ok(());
+        }
+
+        write!(f, "\n\n{indent}-- terminal", indent = self.indent)?;
+        for (index, color) in colors.iter().enumerate() {
+            if let Some(color) = color {
+                write!(
+                    f,
+                    "\n{indent}vim.g.terminal_color_{index} = {color}",
+                    indent = self.indent
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    canonicalize::{canonicalize, highlight_tables, CanonicalizeError, CanonicalizeOptions},
+    check::contrast::wcag_contrast,
+    information::Background,
+    palette::{Oklab, Oklch, RgbColor},
+    sections::color_spec::{
+        parser::{Color, LongForm, SpecString},
+        ColorSpec,
+    },
+    Template,
+};
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+use toml_edit::{ser::ValueSerializer, value, Document, Formatted, Item, Value};
+
+/// The lightness of the light background is `1 - BACKGROUND_SCALE * l` of the dark one, e.g.
+/// 0.2 becomes 0.97.
+const BACKGROUND_SCALE: f64 = 0.15;
+const BISECTION_STEPS: usize = 24;
+
+#[derive(Debug, thiserror::Error)]
+pub enum VariantError {
+    #[error(transparent)]
+    Template(#[from] toml::de::Error),
+    #[error(transparent)]
+    Toml(#[from] toml_edit::TomlError),
+    #[error(transparent)]
+    Canonicalize(#[from] CanonicalizeError),
+    #[error("The template already has a light background")]
+    AlreadyLight,
+    #[error("Neither `Normal` nor the palette define a background color")]
+    MissingBackground,
+}
+
+/// Maps the colors of a dark theme to their counterparts on a light background.
+///
+/// Lightness is inverted in OKLCH: colors lighter than the dark background become darker than
+/// the light one and the other way around. The lightness is chosen so that the WCAG contrast
+/// against the new background is the one the color had against the old background, hue and
+/// chroma are kept as far as the sRGB gamut allows.
+#[derive(Debug, Clone, Copy)]
+pub struct LightMapping {
+    dark_bg: RgbColor,
+    dark_lightness: f64,
+    light_bg: RgbColor,
+    light_lightness: f64,
+}
+
+impl LightMapping {
+    pub fn new(dark_bg: RgbColor) -> Self {
+        let dark = Oklch::from(Oklab::from(dark_bg));
+        let light_lightness = 1.0 - BACKGROUND_SCALE * dark.l;
+        LightMapping {
+            dark_bg,
+            dark_lightness: dark.l,
+            light_bg: Oklch {
+                l: light_lightness,
+                ..dark
+            }
+            .to_rgb_in_gamut(),
+            light_lightness,
+        }
+    }
+
+    pub fn map(&self, color: RgbColor) -> RgbColor {
+        if color == self.dark_bg {
+            return self.light_bg;
+        }
+
+        let contrast = wcag_contrast(color, self.dark_bg);
+        let color = Oklch::from(Oklab::from(color));
+        // the contrast grows with the distance in lightness from the background, search the
+        // side of the light background opposite to the side the color was on
+        let darker = color.l >= self.dark_lightness;
+        let (mut low, mut high) = if darker {
+            (0.0, self.light_lightness)
+        } else {
+            (self.light_lightness, 1.0)
+        };
+        let at = |l: f64| Oklch { l, ..color }.to_rgb_in_gamut();
+        for _ in 0..BISECTION_STEPS {
+            let l = (low + high) / 2.0;
+            let too_close = wcag_contrast(at(l), self.light_bg) < contrast;
+            if too_close == darker {
+                high = l;
+            } else {
+                low = l;
+            }
+        }
+        at((low + high) / 2.0)
+    }
+}
+
+/// Derives a light variant of a dark template: the palette colors and the colors written as hex
+/// in the highlight groups, `[terminal]`, `[lualine]`, `[tmux]` and `[base16]` are mapped with
+/// [`LightMapping`] and `information` gets `background = "light"` and a new name.
+pub fn derive_light(input: &str, name: Option<&str>) -> Result<String, VariantError> {
+    let template: Template = input.parse()?;
+    if let Background::Light = template.information.background {
+        return Err(VariantError::AlreadyLight);
+    }
+    let palette = &template.palette;
+    let dark_bg = template
+        .sections
+        .resolve("Normal")
+        .and_then(|format| format.bg.as_ref())
+        .and_then(|color| color.resolve(palette))
+        .or_else(|| palette.0.get("bg").copied())
+        .ok_or(VariantError::MissingBackground)?;
+    let mapping = LightMapping::new(dark_bg);
+
+    let mut document: Document = input.parse()?;
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => light_name(&template.information.name),
+    };
+    let information = &mut document["information"];
+    information["name"] = value(name);
+    information["background"] = value(Background::Light.to_string());
+
+    if let Some(table) = document["palette"].as_table_mut() {
+        for (name, item) in table.iter_mut() {
+            let Some(value) = item.as_value_mut() else {
+                continue;
+            };
+            let color = palette.0[name.get()];
+            let decor = value.decor().clone();
+            *value = Value::String(Formatted::new(mapping.map(color).to_string()));
+            *value.decor_mut() = decor;
+        }
+    }
+
+    for table in highlight_tables(&mut document) {
+        for (_, item) in table.iter_mut() {
+            if let Some(value) = item.as_value_mut() {
+                map_hex_colors(value, &mapping);
+            }
+        }
+    }
+    if let Some(tmux) = document.get_mut("tmux").and_then(Item::as_table_like_mut) {
+        for (_, item) in tmux.iter_mut() {
+            if let Some(value) = item.as_value_mut() {
+                map_hex_colors(value, &mapping);
+            }
+        }
+    }
+    for name in ["terminal", "base16"] {
+        if let Some(table) = document.get_mut(name).and_then(Item::as_table_like_mut) {
+            for (_, item) in table.iter_mut() {
+                if let Some(value) = item.as_value_mut() {
+                    map_hex_color(value, &mapping);
+                }
+            }
+        }
+    }
+    if let Some(lualine) = document
+        .get_mut("lualine")
+        .and_then(Item::as_table_like_mut)
+    {
+        for (_, mode) in lualine.iter_mut() {
+            let Some(mode) = mode.as_table_like_mut() else {
+                continue;
+            };
+            for (_, colors) in mode.iter_mut() {
+                let Some(colors) = colors.as_table_like_mut() else {
+                    continue;
+                };
+                for key in ["fg", "bg"] {
+                    if let Some(value) = colors.get_mut(key).and_then(Item::as_value_mut) {
+                        map_hex_color(value, &mapping);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(canonicalize(
+        &document.to_string(),
+        CanonicalizeOptions::default(),
+    )?)
+}
+
+/// Maps the colors of a color spec that are written as hex, palette colors are left to the
+/// palette. Specs that don't parse are left for [`canonicalize`] to report.
+fn map_hex_colors(value: &mut Value, mapping: &LightMapping) {
+    let Ok(mut color_spec) = ColorSpec::deserialize(value.clone().into_deserializer()) else {
+        return;
+    };
+    let ColorSpec::Color(format) = &mut color_spec else {
+        return;
+    };
+    let mut mapped = false;
+    for color in [&mut format.fg, &mut format.bg, &mut format.special]
+        .into_iter()
+        .flatten()
+    {
+        if let Color::Color(rgb) = color {
+            *rgb = mapping.map(*rgb);
+            mapped = true;
+        }
+    }
+    if !mapped {
+        return;
+    }
+
+    let decor = value.decor().clone();
+    *value = if value.is_inline_table() {
+        LongForm(&color_spec)
+            .serialize(ValueSerializer::new())
+            .expect("a color spec is a table of strings")
+    } else {
+        SpecString(&color_spec).to_string().into()
+    };
+    *value.decor_mut() = decor;
+}
+
+/// Maps a single color written as hex, like the ones of `[terminal]`, names of palette colors
+/// are kept.
+fn map_hex_color(value: &mut Value, mapping: &LightMapping) {
+    let Some(color) = value
+        .as_str()
+        .and_then(|color| color.parse::<RgbColor>().ok())
+    else {
+        return;
+    };
+    let decor = value.decor().clone();
+    *value = mapping.map(color).to_string().into();
+    *value.decor_mut() = decor;
+}
+
+/// The name of the light variant, e.g. `gruvbox-light` for `gruvbox-dark` or `onedark-light`
+/// for `onedark`.
+fn light_name(name: &str) -> String {
+    let base = ["-dark", "_dark", " dark"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .unwrap_or(name);
+    format!("{base}-light")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::AnsiColor;
+
+    #[test]
+    fn hex_colors_of_highlight_groups_are_mapped() {
+        let input = r##"[information]
+name = "night-dark"
+author = "me"
+background = "dark"
+
+[palette]
+bg = "#1a1b26"
+fg = "#c0caf5"
+
+[highlights]
+Normal = "fg bg"
+Comment = "#565f89 - i" # dim
+Visual = { bg = "#33467c" }
+
+[semantic_tokens]
+type.interface = "#7dcfff"
+
+[terminal]
+black = "#101010"
+red = "fg"
+
+[lualine.normal]
+a = { fg = "#101010", bg = "#dddddd", gui = "b" }
+b = { fg = "fg", bg = "bg" }
+
+[tmux]
+status = "#dddddd #101010"
+"##;
+        let output = derive_light(input, None).unwrap();
+        let mapping = LightMapping::new("#1a1b26".parse().unwrap());
+        let map = |hex: &str| mapping.map(hex.parse().unwrap());
+
+        assert!(output.contains(r#"Normal  = "fg bg""#));
+        assert!(output.contains(&format!(r#"Comment = "{} - i" # dim"#, map("#565f89"))));
+        assert!(output.contains(&format!(r#"Visual  = {{ bg = "{}" }}"#, map("#33467c"))));
+        assert!(output.contains(&format!(r#"type.interface = "{}""#, map("#7dcfff"))));
+        assert!(output.contains(&format!(
+            r#"status = "{} {}""#,
+            map("#dddddd"),
+            map("#101010")
+        )));
+
+        let template: Template = output.parse().unwrap();
+        let hex = |color: Option<&Color>| match color {
+            Some(Color::Color(rgb)) => Some(*rgb),
+            _ => None,
+        };
+        let terminal = &template.terminal.unwrap().0;
+        assert_eq!(hex(terminal.get(&AnsiColor::Black)), Some(map("#101010")));
+        assert!(matches!(&terminal[&AnsiColor::Red], Color::PaletteRef(name) if name == "fg"));
+        let lualine = template.lualine.unwrap().normal.unwrap();
+        let a = lualine.a.unwrap();
+        assert_eq!(hex(a.fg.as_ref()), Some(map("#101010")));
+        assert_eq!(hex(a.bg.as_ref()), Some(map("#dddddd")));
+        assert!(matches!(lualine.b.unwrap().fg, Some(Color::PaletteRef(_))));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{
+    global::Global,
+    information::Information,
+    palette::Palette,
+    sections::{
+        color_spec::{parser::Color, ColorFormat, ColorSpec, Style},
+        Sections,
+    },
+    terminal::Terminal,
+};
+use std::fmt::{self, Display, Formatter};
+
+/// A `colors/<name>.vim` colorscheme for Vim, using `hi` commands.
+pub struct VimScript<'a> {
+    pub information: &'a Information,
+    pub palette: &'a Palette,
+    pub global: Option<&'a Global>,
+    pub terminal: Option<&'a Terminal>,
+    pub sections: &'a Sections,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VimScriptWarning {
+    #[error("{section_name}.{highlight_group}: Vim doesn't support blend, it is skipped")]
+    Blend {
+        section_name: String,
+        highlight_group: String,
+    },
+    #[error(
+        "{section_name}.{highlight_group}: Vim doesn't support `@` in group names, it is skipped"
+    )]
+    Capture {
+        section_name: String,
+        highlight_group: String,
+    },
+    #[error(
+        "{section_name}.{highlight_group}: Vim doesn't support links to `{link}`, it is skipped"
+    )]
+    CaptureLink {
+        section_name: String,
+        highlight_group: String,
+        link: String,
+    },
+}
+
+impl VimScript<'_> {
+    /// Everything in the template that can't be expressed in Vimscript.
+    pub fn warnings(&self) -> Vec<VimScriptWarning> {
+        let mut warnings = Vec::new();
+        for (section_name, section) in &self.sections.0 {
+            for (hl_group, color_spec) in &section.0 {
+                if hl_group.starts_with('@') {
+                    warnings.push(VimScriptWarning::Capture {
+                        section_name: section_name.clone(),
+                        highlight_group: hl_group.clone(),
+                    });
+                    continue;
+                }
+                match color_spec {
+                    ColorSpec::Link(link) if link.starts_with('@') => {
+                        warnings.push(VimScriptWarning::CaptureLink {
+                            section_name: section_name.clone(),
+                            highlight_group: hl_group.clone(),
+                            link: link.clone(),
+                        })
+                    }
+                    ColorSpec::Color(ColorFormat { blend: Some(_), .. }) => {
+                        warnings.push(VimScriptWarning::Blend {
+                            section_name: section_name.clone(),
+                            highlight_group: hl_group.clone(),
+                        })
+                    }
+                    _ => (),
+                }
+            }
+        }
+        warnings
+    }
+
+    fn fmt_color(&self, color: &Option<Color>) -> String {
+        color
+            .as_ref()
+            .and_then(|color| color.resolve(self.palette))
+            .map_or_else(|| "NONE".to_string(), |color| color.to_string())
+    }
+
+    fn fmt_cterm_color(&self, color: &Option<Color>) -> String {
+        color
+            .as_ref()
+            .and_then(|color| color.resolve(self.palette))
+            .map_or_else(
+                || "NONE".to_string(),
+                |color| color.to_xterm256().to_string(),
+            )
+    }
+}
+
+impl Display for VimScript<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\
+hi clear
+if exists('syntax_on')
+  syntax reset
+endif
+
+set background={background}",
+            background = self.information.background
+        )?;
+        if self.information.termguicolors {
+            write!(f, "\nset termguicolors")?;
+        }
+        write!(f, "\nlet g:colors_name = '{}'", self.information.name)?;
+
+        if let Some(global) = self.global {
+            writeln!(f)?;
+            for (key, value) in &global.0 {
+                write!(f, "\nlet g:{key} = \"{value}\"")?;
+            }
+        }
+
+        let default_terminal = Terminal::default();
+        let colors = self
+            .terminal
+            .unwrap_or(&default_terminal)
+            .resolve(self.palette)
+            .iter()
+            .map(|color| {
+                color
+                    .as_ref()
+                    .and_then(|color| color.resolve(self.palette))
+                    .map(|color| format!("'{color}'"))
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(colors) = colors {
+            write!(
+                f,
+                "\n\nlet g:terminal_ansi_colors = [{}]",
+                colors.join(", ")
+            )?;
+        }
+
+        for (section_name, section) in &self.sections.0 {
+            write!(f, "\n\n\" {section_name}")?;
+            for (hl_group, color_spec) in &section.0 {
+                if hl_group.starts_with('@') {
+                    continue;
+                }
+                match color_spec {
+                    ColorSpec::Link(link) if link.starts_with('@') => continue,
+                    ColorSpec::Link(link) => write!(f, "\nhi! link {hl_group} {link}")?,
+                    ColorSpec::Color(format) => {
+                        let style = VimStyle(format.style.unwrap_or_else(Style::empty));
+                        write!(
+                            f,
+                            "\nhi {hl_group} guifg={fg} guibg={bg} gui={style}",
+                            fg = self.fmt_color(&format.fg),
+                            bg = self.fmt_color(&format.bg),
+                        )?;
+                        if format.special.is_some() {
+                            write!(f, " guisp={}", self.fmt_color(&format.special))?;
+                        }
+                        if self.information.cterm {
+                            write!(
+                                f,
+                                " ctermfg={fg} ctermbg={bg} cterm={style}",
+                                fg = self.fmt_cterm_color(&format.fg),
+                                bg = self.fmt_cterm_color(&format.bg),
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+
+        writeln!(f)
+    }
+}
+
+/// The attribute names of `gui=`, `cterm=` and `nvim_set_hl`.
+pub(crate) const STYLE_NAMES: [(&str, Style); 11] = [
+    ("standout", Style::STANDOUT),
+    ("underline", Style::UNDERLINE),
+    ("undercurl", Style::UNDERCURL),
+    ("underdouble", Style::UNDERDOUBLE),
+    ("underdotted", Style::UNDERDOTTED),
+    ("underdashed", Style::UNDERDASHED),
+    ("strikethrough", Style::STRIKETHROUGH),
+    ("italic", Style::ITALIC),
+    ("bold", Style::BOLD),
+    ("reverse", Style::REVERSE),
+    ("nocombine", Style::NOCOMBINE),
+];
+
+/// Formats a [`Style`] as the comma separated attribute list of `gui=` and `cterm=`.
+pub(crate) struct VimStyle(pub Style);
+
+impl Display for VimStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "NONE");
+        }
+        let names = STYLE_NAMES
+            .iter()
+            .filter(|(_, flag)| self.0.contains(*flag))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        write!(f, "{}", names.join(","))
+    }
+}